@@ -234,6 +234,7 @@ pub fn handle_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
     match event {
         tauri::WindowEvent::CloseRequested { .. } => {
             if label == "main" {
+                crate::project::manager::release_all_project_locks();
                 if let Some(overlay) = app_handle.get_webview_window("fullscreen-overlay") {
                     let _ = overlay.close();
                 }
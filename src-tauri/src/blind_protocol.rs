@@ -0,0 +1,112 @@
+//! `amv-blind://` scheme: serves the clips of an open blind project under
+//! their entry code, so the webview never sees the real file path.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use tauri::http::{header, Request, Response, StatusCode};
+
+use crate::project::manager::blind;
+
+/// Largest chunk returned for one range request.
+const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+    match extension.to_lowercase().as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "mov" => "video/quicktime",
+        "avi" => "video/x-msvideo",
+        _ => "application/octet-stream",
+    }
+}
+
+fn status(code: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(code)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Parses a single `bytes=start-end` range, capped at `MAX_CHUNK` bytes.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => {
+            let start: u64 = start.parse().ok()?;
+            (start, len.checked_sub(1)?)
+        }
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        ),
+    };
+    if start > end || start >= len {
+        return None;
+    }
+    Some((start, end.min(start + MAX_CHUNK - 1)))
+}
+
+/// Always answers with a `206` of at most `MAX_CHUNK` bytes, even without a
+/// `Range` header, so a whole video is never read into memory.
+pub fn handle(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    // `/<code>` or `/<code>.<ext>`, as listed by `scan_video_folder`.
+    let name = request.uri().path().trim_start_matches('/');
+    let code = name.split('.').next().unwrap_or(name);
+    let Some(path) = blind::resolve_code(code) else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    let Ok(mut file) = File::open(&path) else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    let len = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    if len == 0 {
+        return Response::builder()
+            .header(header::CONTENT_TYPE, mime_type(&path))
+            .header(header::ACCEPT_RANGES, "bytes")
+            .body(Vec::new())
+            .unwrap_or_default();
+    }
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("bytes=0-");
+    let Some((start, end)) = parse_range(range, len) else {
+        return Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new())
+            .unwrap_or_default();
+    };
+
+    let mut body = Vec::new();
+    let read = file
+        .seek(SeekFrom::Start(start))
+        .and_then(|_| (&mut file).take(end - start + 1).read_to_end(&mut body));
+    if read.is_err() {
+        return status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    Response::builder()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_TYPE, mime_type(&path))
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_LENGTH, body.len())
+        .header(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, len),
+        )
+        .body(body)
+        .unwrap_or_default()
+}
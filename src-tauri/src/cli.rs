@@ -0,0 +1,370 @@
+//! Headless entry point used by the `amv-notation-cli` binary: validation,
+//! judge merges, rankings and exports straight from project files, without
+//! starting Tauri or the embedded player.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::project::manager::{self, AggregationMethod, JudgeMerge, RankingResult};
+
+const USAGE: &str = "\
+Usage: amv-notation-cli <command> [options]
+
+Commands:
+  validate <file.json>...
+      Check project or barème files and list every issue.
+  merge <project.json> <judge.json>... --output <merged.json>
+      Add judge project files to the project's imported judges.
+  combine <judge.json>... --output <results.json> [--name <name>]
+      Build one results project from every judge's project file.
+  rank <project.json> [--method <method>] [--category <name>] [--json]
+      Print the final ranking as a table, or as JSON.
+  export <project.json> --output <results.csv|results.xlsx|results.ods>
+      Write the results workbook.
+
+Options:
+  --bareme <bareme.json>  Barème to use when the project does not embed one
+                          and it is not in the barèmes folder.
+  --method <method>       mean (default), median, trimmedMean, zScore or rankAverage.
+";
+
+/// Exit codes: 0 on success, 1 when the files are invalid or a command
+/// failed, 2 for a usage error.
+pub fn run(args: Vec<String>) -> i32 {
+    let Some((command, rest)) = args.split_first() else {
+        eprint!("{}", USAGE);
+        return 2;
+    };
+    let options = match Options::parse(rest) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return 2;
+        }
+    };
+    let result = match command.as_str() {
+        "validate" => validate(&options),
+        "merge" => merge(&options),
+        "combine" => combine(&options),
+        "rank" => rank(&options),
+        "export" => export(&options),
+        "help" | "--help" | "-h" => {
+            print!("{}", USAGE);
+            return 0;
+        }
+        other => {
+            eprintln!("Unknown command: {}\n\n{}", other, USAGE);
+            return 2;
+        }
+    };
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(message) => {
+            eprintln!("{}", message);
+            1
+        }
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    files: Vec<String>,
+    output: Option<String>,
+    bareme: Option<String>,
+    name: Option<String>,
+    method: AggregationMethod,
+    category: Option<String>,
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| format!("Missing value for {}", name))
+            };
+            match arg.as_str() {
+                "--output" | "-o" => options.output = Some(value(arg)?),
+                "--bareme" => options.bareme = Some(value(arg)?),
+                "--category" => options.category = Some(value(arg)?),
+                "--name" => options.name = Some(value(arg)?),
+                "--method" => {
+                    let method = value(arg)?;
+                    options.method =
+                        serde_json::from_value(serde_json::Value::String(method.clone()))
+                            .map_err(|_| format!("Unknown aggregation method: {}", method))?;
+                }
+                "--json" => options.json = true,
+                flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+                file => options.files.push(file.to_string()),
+            }
+        }
+        Ok(options)
+    }
+
+    fn project(&self) -> Result<serde_json::Value, String> {
+        let [path] = self.files.as_slice() else {
+            return Err("Expected exactly one project file".to_string());
+        };
+        let mut data = read(path)?;
+        if let Some(bareme) = &self.bareme {
+            let bareme = read_json(bareme)?;
+            if !bareme["criteria"].is_array() {
+                return Err(format!(
+                    "Not a barème file: {}",
+                    self.bareme.as_deref().unwrap_or("")
+                ));
+            }
+            // Same place the frontend embeds the barème in exported projects.
+            if let serde_json::Value::Object(root) = &mut data {
+                root.insert("bareme".to_string(), bareme);
+            }
+        }
+        Ok(data)
+    }
+
+    fn output(&self) -> Result<&str, String> {
+        self.output
+            .as_deref()
+            .ok_or_else(|| "Missing --output <file>".to_string())
+    }
+}
+
+/// Reads without taking the project lock, so a file open in the app can be used.
+fn read(path: &str) -> Result<serde_json::Value, String> {
+    manager::load_project(path.to_string(), None, Some(true))
+}
+
+/// Reads a file as it is, without the project migrations `read` applies.
+fn read_json(path: &str) -> Result<serde_json::Value, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path, e))
+}
+
+fn is_bareme(value: &serde_json::Value) -> bool {
+    value["criteria"].is_array() && value.get("project").is_none()
+}
+
+fn validate(options: &Options) -> Result<bool, String> {
+    if options.files.is_empty() {
+        return Err("Expected at least one file to validate".to_string());
+    }
+    let mut valid = true;
+    for path in &options.files {
+        let value = match read_json(path) {
+            Ok(value) => value,
+            Err(message) => {
+                println!("{}: {}", path, message);
+                valid = false;
+                continue;
+            }
+        };
+        let (kind, issues): (&str, Vec<(String, String)>) = if is_bareme(&value) {
+            let issues = manager::validate_bareme(value);
+            (
+                "barème",
+                issues
+                    .into_iter()
+                    .map(|issue| (issue.path, issue.message))
+                    .collect(),
+            )
+        } else {
+            let issues = manager::validate_project(value);
+            (
+                "project",
+                issues
+                    .into_iter()
+                    .map(|issue| (issue.path, issue.message))
+                    .collect(),
+            )
+        };
+        if issues.is_empty() {
+            println!("{}: valid {}", path, kind);
+            continue;
+        }
+        valid = false;
+        println!("{}: {} issue(s) in {}", path, issues.len(), kind);
+        for (field, message) in issues {
+            println!("  {}: {}", field, message);
+        }
+    }
+    Ok(valid)
+}
+
+/// Prints the per-file summary and the conflict report; returns whether the
+/// merge is clean.
+fn print_merge(merge: &JudgeMerge) -> bool {
+    for judge in &merge.judges {
+        println!(
+            "{}: {} ({} clip(s) matched)",
+            judge.source, judge.judge_name, judge.matched_clips
+        );
+        for clip in &judge.unmatched_clips {
+            println!("  not in project: {}", clip);
+        }
+    }
+    let conflicts = &merge.conflicts;
+    for duplicate in &conflicts.duplicate_judges {
+        println!(
+            "Duplicate judge name {} in {}: renamed {}",
+            duplicate.judge_name,
+            duplicate.sources.join(", "),
+            duplicate.renamed_to.join(", ")
+        );
+    }
+    for mismatch in &conflicts.bareme_mismatches {
+        println!(
+            "Barème mismatch in {} ({}): {} instead of {}",
+            mismatch.source,
+            mismatch.judge_name,
+            if mismatch.bareme_id == mismatch.expected_bareme_id {
+                "another revision".to_string()
+            } else {
+                mismatch.bareme_id.clone()
+            },
+            mismatch.expected_bareme_id
+        );
+    }
+    for clip in &conflicts.partial_clips {
+        println!("{}: not scored by {}", clip.label, clip.missing.join(", "));
+    }
+    merge.judges.iter().all(|judge| judge.matched_clips > 0)
+        && conflicts.duplicate_judges.is_empty()
+        && conflicts.bareme_mismatches.is_empty()
+}
+
+fn merge(options: &Options) -> Result<bool, String> {
+    let Some((project, judges)) = options.files.split_first() else {
+        return Err("Expected a project file and judge files".to_string());
+    };
+    if judges.is_empty() {
+        return Err("Expected at least one judge file".to_string());
+    }
+    let output = options.output()?;
+    let merge = manager::merge_judge_projects(read(project)?, judges.to_vec())?;
+    let clean = print_merge(&merge);
+    manager::export_json(merge.project, output.to_string())?;
+    println!("Written {}", output);
+    Ok(clean)
+}
+
+fn combine(options: &Options) -> Result<bool, String> {
+    if options.files.is_empty() {
+        return Err("Expected at least one judge file".to_string());
+    }
+    let output = options.output()?;
+    let merge = manager::merge_judge_files(options.files.clone(), options.name.clone())?;
+    let clean = print_merge(&merge);
+    manager::export_json(merge.project, output.to_string())?;
+    println!("Written {}", output);
+    Ok(clean)
+}
+
+fn format_score(score: Option<f64>) -> String {
+    score.map_or_else(|| "-".to_string(), |score| score.to_string())
+}
+
+fn print_table(ranking: &RankingResult) {
+    let with_category = ranking
+        .clips
+        .iter()
+        .any(|clip| clip.contest_category.is_some());
+    let mut rows = vec![{
+        let mut header = vec!["Rank".to_string(), "Clip".to_string()];
+        if with_category {
+            header.push("Category".to_string());
+        }
+        header.push("Score".to_string());
+        header
+    }];
+    for clip in &ranking.clips {
+        let mut row = vec![
+            format!("{}{}", clip.rank, if clip.tied { "=" } else { "" }),
+            clip.label.clone(),
+        ];
+        if with_category {
+            row.push(clip.contest_category.clone().unwrap_or_default());
+        }
+        row.push(format_score(clip.score));
+        rows.push(row);
+    }
+
+    let mut widths = vec![0; rows[0].len()];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = " ".repeat(width - cell.chars().count());
+                format!("{}{}", cell, padding)
+            })
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+
+    for tie in &ranking.ties {
+        let rule = tie
+            .tie_breaker
+            .as_ref()
+            .and_then(|rule| serde_json::to_value(rule).ok())
+            .and_then(|rule| rule["type"].as_str().map(str::to_string))
+            .unwrap_or_else(|| "no rule".to_string());
+        println!(
+            "Tie at rank {} ({} clip(s)): {}, {}",
+            tie.rank,
+            tie.clip_ids.len(),
+            rule,
+            if tie.resolved {
+                "resolved"
+            } else {
+                "unresolved"
+            }
+        );
+    }
+}
+
+fn rank(options: &Options) -> Result<bool, String> {
+    let ranking = manager::rank_results(
+        options.project()?,
+        Some(options.method),
+        None,
+        options.category.clone(),
+    )?;
+    if options.json {
+        let json = serde_json::to_string_pretty(&ranking).map_err(|e| e.to_string())?;
+        println!("{}", json);
+    } else {
+        print_table(&ranking);
+    }
+    Ok(true)
+}
+
+fn export(options: &Options) -> Result<bool, String> {
+    let data = options.project()?;
+    let output = options.output()?.to_string();
+    let extension = Path::new(&output)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let labels = Some(HashMap::new());
+    match extension.as_str() {
+        "csv" => manager::export_results_csv(data, output.clone(), labels)?,
+        "xlsx" => manager::export_results_xlsx(data, output.clone(), labels)?,
+        "ods" => manager::export_results_ods(data, output.clone(), labels)?,
+        _ => return Err(format!("Unsupported export format: {}", output)),
+    }
+    println!("Written {}", output);
+    Ok(true)
+}
@@ -1,4 +1,6 @@
 mod app_windows;
+mod blind_protocol;
+pub mod cli;
 mod player;
 mod project;
 mod state;
@@ -29,6 +31,9 @@ pub fn run() {
             player::bootstrap::initialize_embedded_player(app, &state);
             Ok(())
         })
+        .register_uri_scheme_protocol("amv-blind", |_ctx, request| {
+            blind_protocol::handle(&request)
+        })
         .on_window_event(|window, event| {
             app_windows::handle_window_event(window, event);
         })
@@ -75,15 +80,58 @@ pub fn run() {
             // Project commands
             project::manager::save_project,
             project::manager::load_project,
+            project::manager::release_project_lock,
+            project::manager::get_project_lock,
+            project::manager::watch_project_files,
+            project::manager::unwatch_project_files,
+            project::manager::validate_project,
+            project::manager::list_project_snapshots,
+            project::manager::restore_project_snapshot,
+            project::manager::diff_project_snapshot,
             project::manager::export_json,
+            project::manager::export_project_bundle,
+            project::manager::import_project_bundle,
+            project::manager::find_missing_clips,
+            project::manager::propose_clip_relinks,
+            project::manager::apply_clip_relinks,
             project::manager::delete_project,
             project::manager::get_default_projects_folder,
             project::manager::get_default_baremes_folder,
             project::manager::list_projects_in_folder,
+            project::manager::search_projects,
             project::manager::ensure_directory_exists,
             project::manager::save_bareme,
+            project::manager::validate_bareme,
+            project::manager::import_bareme_from_sheet,
+            project::manager::import_judge_sheet,
+            project::manager::export_results_xlsx,
+            project::manager::export_results_ods,
+            project::manager::aggregate_results,
+            project::manager::rank_results,
+            project::manager::judge_statistics,
+            project::manager::merge_judge_projects,
+            project::manager::merge_judge_files,
+            project::manager::enable_blind_mode,
+            project::manager::open_blind_session,
+            project::manager::close_blind_session,
+            project::manager::reveal_blind_identities,
+            project::manager::disable_blind_mode,
+            project::manager::plan_clip_order,
+            project::manager::apply_clip_order,
+            project::manager::query_audit_log,
+            project::manager::verify_audit_log,
+            project::manager::export_audit_log_csv,
+            project::manager::export_results_csv,
+            project::manager::list_bareme_revisions,
+            project::manager::check_bareme_revision_pin,
+            project::manager::repin_bareme_revision,
+            project::manager::load_bareme_revision,
+            project::manager::diff_bareme_revisions,
             project::manager::delete_bareme,
             project::manager::load_baremes,
+            project::manager::list_quarantined_baremes,
+            project::manager::restore_quarantined_bareme,
+            project::manager::purge_quarantined_baremes,
             project::manager::save_user_settings,
             project::manager::load_user_settings,
             // Window commands
@@ -1,3 +1,4 @@
+use crate::project::manager::blind;
 use crate::state::AppState;
 use tauri::State;
 
@@ -8,7 +9,10 @@ pub fn player_load(state: State<'_, AppState>, path: String) -> Result<(), Strin
     with_player(
         &state,
         "Player not initialized. Make sure mpv-2.dll is available.",
-        |p| p.load_file(&path),
+        |p| {
+            p.load_file(&blind::resolve_path(&path))
+                .map_err(|error| blind::redact_error(error, &path))
+        },
     )
 }
 
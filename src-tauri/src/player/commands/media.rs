@@ -1,3 +1,4 @@
+use crate::project::manager::blind;
 use crate::state::AppState;
 use std::sync::mpsc;
 use std::time::Duration;
@@ -11,7 +12,7 @@ pub async fn player_get_media_info(
     use std::panic::{catch_unwind, AssertUnwindSafe};
 
     if let Some(target_path) = path {
-        let trimmed = target_path.trim().to_string();
+        let trimmed = blind::resolve_path(target_path.trim());
         if !trimmed.is_empty() {
             let normalized_target = super::parsing::normalize_path(&trimmed);
             if let Some(cached) = super::cache::get_media_info_cached(&normalized_target) {
@@ -86,7 +87,8 @@ pub async fn player_get_frame_preview(
     seconds: f64,
     width: Option<u32>,
 ) -> Result<String, String> {
-    let mut target_path = path.unwrap_or_default().trim().to_string();
+    let requested_path = path.unwrap_or_default().trim().to_string();
+    let mut target_path = blind::resolve_path(&requested_path);
     if target_path.is_empty() {
         let player = state.player.lock().map_err(|e| e.to_string())?;
         if let Some(p) = &*player {
@@ -127,6 +129,6 @@ pub async fn player_get_frame_preview(
             );
             Ok(image)
         }
-        Err(error) => Err(error),
+        Err(error) => Err(blind::redact_error(error, &requested_path)),
     }
 }
@@ -1,24 +1,128 @@
 #![allow(dead_code)]
+use std::collections::HashMap;
 use std::path::Path;
 
+mod aggregation;
+mod audit_log;
+mod bareme_import;
+mod bareme_revisions;
+mod bareme_trash;
+mod bareme_validation;
 mod baremes;
+pub(crate) mod blind;
+mod bundle;
+mod clip_links;
+mod clip_order;
+mod csv_export;
+mod file_watch;
+mod history;
 mod json_io;
+mod judge_merge;
+mod judge_sheet_import;
+mod judge_stats;
+mod migrations;
+mod ods_export;
 mod paths;
 mod project_files;
 mod project_listing;
+mod project_lock;
+mod project_search;
+mod project_validation;
+mod ranking;
+mod results;
+mod results_workbook;
+mod spreadsheet;
 mod types;
 mod user_settings;
+mod xlsx_export;
 
-pub use types::ProjectSummary;
+pub use types::{
+    AggregatedResults, AggregationMethod, AuditLog, AuditQuery, AuditVerification, BaremeRevision,
+    BaremeRevisionDiff, BaremeRevisionPinStatus, BaremeValidationIssue, BlindIdentity,
+    BlindSessionStatus, ClipOrderPlan, ClipOrderSettings, ClipRelinkProposal,
+    ImportedProjectBundle, ImportedSheetBareme, JudgeMerge, JudgeSheetImport, JudgeStatistics,
+    MissingClip, ProjectLockInfo, ProjectSearchGroup, ProjectSearchQuery, ProjectSnapshot,
+    ProjectSnapshotDiff, ProjectSummary, ProjectValidationIssue, QuarantinedBareme, RankingResult,
+    TieBreaker,
+};
 
+/// `reason`, when given, is recorded in the audit log with this save's score changes.
 #[tauri::command]
-pub fn save_project(data: serde_json::Value, file_path: String) -> Result<(), String> {
-    project_files::save_project_file(data, file_path)
+pub fn save_project(
+    data: serde_json::Value,
+    file_path: String,
+    reason: Option<String>,
+) -> Result<(), String> {
+    project_files::save_project_file(data, file_path, reason.as_deref())
 }
 
 #[tauri::command]
-pub fn load_project(file_path: String) -> Result<serde_json::Value, String> {
-    project_files::load_project_file(file_path)
+pub fn load_project(
+    file_path: String,
+    take_over: Option<bool>,
+    read_only: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    if read_only.unwrap_or(false) {
+        return project_files::read_project_file(file_path);
+    }
+    project_files::load_project_file(file_path, take_over.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn release_project_lock(file_path: String) -> Result<(), String> {
+    project_lock::release(Path::new(&file_path))
+}
+
+/// Starts reporting external changes to `file_path` and to the barèmes folder
+/// through `project:external-change` / `baremes:external-change` events.
+#[tauri::command]
+pub fn watch_project_files(
+    app_handle: tauri::AppHandle,
+    file_path: Option<String>,
+) -> Result<(), String> {
+    file_watch::watch(app_handle, file_path.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+pub fn unwatch_project_files() {
+    file_watch::unwatch();
+}
+
+#[tauri::command]
+pub fn get_project_lock(file_path: String) -> Option<ProjectLockInfo> {
+    project_lock::current_lock(Path::new(&file_path))
+}
+
+/// Drops the project locks of this instance when the app closes.
+pub fn release_all_project_locks() {
+    project_lock::release_all();
+}
+
+#[tauri::command]
+pub fn validate_project(data: serde_json::Value) -> Vec<ProjectValidationIssue> {
+    project_validation::project_issues(&data)
+}
+
+#[tauri::command]
+pub fn list_project_snapshots(file_path: String) -> Result<Vec<ProjectSnapshot>, String> {
+    history::list_snapshots(file_path)
+}
+
+#[tauri::command]
+pub fn restore_project_snapshot(
+    file_path: String,
+    snapshot_id: String,
+) -> Result<serde_json::Value, String> {
+    project_files::restore_snapshot(file_path, snapshot_id)
+}
+
+#[tauri::command]
+pub fn diff_project_snapshot(
+    file_path: String,
+    snapshot_id: String,
+    against_snapshot_id: Option<String>,
+) -> Result<ProjectSnapshotDiff, String> {
+    history::diff_snapshot(file_path, snapshot_id, against_snapshot_id)
 }
 
 #[tauri::command]
@@ -26,6 +130,54 @@ pub fn export_json(data: serde_json::Value, file_path: String) -> Result<(), Str
     project_files::export_json_file(data, file_path)
 }
 
+#[tauri::command]
+pub fn export_project_bundle(
+    data: serde_json::Value,
+    bundle_path: String,
+    screenshot_paths: Option<Vec<String>>,
+) -> Result<(), String> {
+    bundle::export_bundle(data, bundle_path, screenshot_paths.unwrap_or_default())
+}
+
+/// `overwrite` must be set to replace an existing project file.
+#[tauri::command]
+pub fn import_project_bundle(
+    bundle_path: String,
+    project_path: String,
+    clips_folder: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<ImportedProjectBundle, String> {
+    bundle::import_bundle(
+        bundle_path,
+        project_path,
+        clips_folder,
+        overwrite.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub fn find_missing_clips(data: serde_json::Value) -> Vec<MissingClip> {
+    clip_links::find_missing_clips(&data)
+}
+
+#[tauri::command]
+pub fn propose_clip_relinks(
+    data: serde_json::Value,
+    folder_path: String,
+    use_content_hash: Option<bool>,
+) -> Result<Vec<ClipRelinkProposal>, String> {
+    clip_links::propose_relinks(&data, &folder_path, use_content_hash.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn apply_clip_relinks(
+    data: serde_json::Value,
+    relinks: HashMap<String, String>,
+    clips_folder_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    clip_links::apply_relinks(data, relinks, clips_folder_path)
+}
+
 #[tauri::command]
 pub fn delete_project(file_path: String) -> Result<(), String> {
     project_files::delete_project_file(file_path)
@@ -37,8 +189,19 @@ pub fn get_default_projects_folder() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn list_projects_in_folder(folder_path: String) -> Result<Vec<ProjectSummary>, String> {
-    project_listing::list_projects(folder_path)
+pub fn list_projects_in_folder(
+    folder_path: String,
+    max_depth: Option<usize>,
+) -> Result<Vec<ProjectSummary>, String> {
+    match max_depth {
+        Some(depth) if depth > 1 => project_listing::list_projects_recursive(folder_path, depth),
+        _ => project_listing::list_projects(folder_path),
+    }
+}
+
+#[tauri::command]
+pub fn search_projects(query: ProjectSearchQuery) -> Result<Vec<ProjectSearchGroup>, String> {
+    project_search::search_projects(query)
 }
 
 #[tauri::command]
@@ -56,6 +219,252 @@ pub fn save_bareme(data: serde_json::Value, bareme_id: String) -> Result<(), Str
     baremes::save_bareme_file(data, bareme_id)
 }
 
+#[tauri::command]
+pub fn validate_bareme(data: serde_json::Value) -> Vec<BaremeValidationIssue> {
+    bareme_validation::bareme_issues(&data)
+}
+
+/// Builds a barème from the two header rows of a CSV, TSV, XLSX or ODS contest sheet.
+#[tauri::command]
+pub fn import_bareme_from_sheet(
+    file_path: String,
+    name: Option<String>,
+) -> Result<ImportedSheetBareme, String> {
+    bareme_import::import_bareme_from_sheet(&file_path, name)
+}
+
+/// Reads another judge's CSV, TSV, XLSX or ODS score sheet against the project's
+/// clips and barème and returns the judge with a match report, without saving.
+#[tauri::command]
+pub fn import_judge_sheet(
+    file_path: String,
+    data: serde_json::Value,
+    judge_name: Option<String>,
+    row_matches: Option<HashMap<usize, String>>,
+) -> Result<JudgeSheetImport, String> {
+    judge_sheet_import::import_judge_sheet(
+        &file_path,
+        &data,
+        judge_name,
+        row_matches.unwrap_or_default(),
+    )
+}
+
+/// Writes the results workbook: global ranking, per-category tables, one tab
+/// per contest category and one sheet per judge, with formulas for totals.
+/// `labels` translates the sheet and column titles.
+#[tauri::command]
+pub fn export_results_xlsx(
+    data: serde_json::Value,
+    file_path: String,
+    labels: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    xlsx_export::export_results_xlsx(data, &file_path, labels.unwrap_or_default())
+}
+
+/// Same sheets as `export_results_xlsx`, as an OpenDocument spreadsheet for
+/// LibreOffice.
+#[tauri::command]
+pub fn export_results_ods(
+    data: serde_json::Value,
+    file_path: String,
+    labels: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    ods_export::export_results_ods(data, &file_path, labels.unwrap_or_default())
+}
+
+/// Combines the current and imported judges' scores with `method` (plain
+/// mean by default), with a per-judge and per-category breakdown per clip.
+#[tauri::command]
+pub fn aggregate_results(
+    data: serde_json::Value,
+    method: Option<AggregationMethod>,
+) -> Result<AggregatedResults, String> {
+    aggregation::aggregate_results(data, method.unwrap_or_default())
+}
+
+/// Final ranking, optionally within one contest category. Ties are broken by
+/// `tie_breakers`, else the project's or the barème's, and each resolution is
+/// reported.
+#[tauri::command]
+pub fn rank_results(
+    data: serde_json::Value,
+    method: Option<AggregationMethod>,
+    tie_breakers: Option<Vec<TieBreaker>>,
+    contest_category: Option<String>,
+) -> Result<RankingResult, String> {
+    ranking::rank_results(
+        data,
+        method.unwrap_or_default(),
+        tie_breakers,
+        contest_category,
+    )
+}
+
+/// Per-judge spread, pairwise Spearman/Kendall agreement, ICC per criterion
+/// and clips where a judge is more than `threshold` points (15% of the
+/// barème by default) away from the others.
+#[tauri::command]
+pub fn judge_statistics(
+    data: serde_json::Value,
+    threshold: Option<f64>,
+) -> Result<JudgeStatistics, String> {
+    judge_stats::judge_statistics(data, threshold)
+}
+
+/// Adds judge project files to the project's imported judges, matching
+/// clips by id, file name or author and title. Returns the merged project
+/// and a conflict report without saving.
+#[tauri::command]
+pub fn merge_judge_projects(
+    data: serde_json::Value,
+    file_paths: Vec<String>,
+) -> Result<JudgeMerge, String> {
+    judge_merge::merge_judge_projects(data, file_paths)
+}
+
+/// Builds one results project from the project files of every judge of a
+/// contest, each as an imported judge, with a report of duplicate judge
+/// names, barème mismatches and clips only some judges scored. Not saved.
+#[tauri::command]
+pub fn merge_judge_files(
+    file_paths: Vec<String>,
+    name: Option<String>,
+) -> Result<JudgeMerge, String> {
+    judge_merge::merge_judge_files(file_paths, name)
+}
+
+/// Same sheets as `export_results_xlsx` in one CSV document, formulas
+/// replaced by their values.
+#[tauri::command]
+pub fn export_results_csv(
+    data: serde_json::Value,
+    file_path: String,
+    labels: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    csv_export::export_results_csv(data, &file_path, labels.unwrap_or_default())
+}
+
+/// Switches the project to blind judging: every clip gets an opaque entry
+/// code, and its name, author and file are sealed with `organizer_key`.
+/// Returns the project to save and send to judges.
+#[tauri::command]
+pub fn enable_blind_mode(
+    data: serde_json::Value,
+    organizer_key: String,
+) -> Result<serde_json::Value, String> {
+    blind::enable(data, &organizer_key)
+}
+
+/// Finds the videos of the blind project opened from `file_path` in its clips
+/// folder so their alias paths play. Called when a project is opened, and
+/// again after its clips folder changes.
+#[tauri::command]
+pub fn open_blind_session(
+    file_path: String,
+    data: serde_json::Value,
+) -> Result<BlindSessionStatus, String> {
+    blind::open_session(&file_path, &data)
+}
+
+#[tauri::command]
+pub fn close_blind_session(file_path: String) {
+    blind::close_session(&file_path);
+}
+
+#[tauri::command]
+pub fn reveal_blind_identities(
+    data: serde_json::Value,
+    organizer_key: String,
+) -> Result<Vec<BlindIdentity>, String> {
+    blind::reveal(data, &organizer_key)
+}
+
+/// Puts the clips' names back and leaves blind mode, for results.
+#[tauri::command]
+pub fn disable_blind_mode(
+    data: serde_json::Value,
+    organizer_key: String,
+) -> Result<serde_json::Value, String> {
+    blind::disable(data, &organizer_key)
+}
+
+/// Each judge's clip order for `settings`, or for the ones stored in the
+/// project. Nothing is changed.
+#[tauri::command]
+pub fn plan_clip_order(
+    data: serde_json::Value,
+    settings: Option<ClipOrderSettings>,
+) -> Result<ClipOrderPlan, String> {
+    clip_order::plan(data, settings)
+}
+
+/// Puts the clips in the judge's drawn order and stores the seed in the
+/// project. Returns the project to save.
+#[tauri::command]
+pub fn apply_clip_order(
+    data: serde_json::Value,
+    settings: Option<ClipOrderSettings>,
+    judge_name: Option<String>,
+) -> Result<serde_json::Value, String> {
+    clip_order::apply(data, settings, judge_name)
+}
+
+/// Score changes recorded for the project file, filtered by clip, criterion
+/// or judge, with the check of the log's hash chain.
+#[tauri::command]
+pub fn query_audit_log(file_path: String, query: Option<AuditQuery>) -> Result<AuditLog, String> {
+    audit_log::query(&file_path, &query.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn verify_audit_log(file_path: String) -> Result<AuditVerification, String> {
+    audit_log::verify(&file_path)
+}
+
+#[tauri::command]
+pub fn export_audit_log_csv(
+    file_path: String,
+    output_path: String,
+    query: Option<AuditQuery>,
+) -> Result<(), String> {
+    audit_log::export_csv(&file_path, &output_path, &query.unwrap_or_default())
+}
+
+/// The project's barème pin compared with its current barème.
+#[tauri::command]
+pub fn check_bareme_revision_pin(data: serde_json::Value) -> BaremeRevisionPinStatus {
+    bareme_revisions::pin_status(&data)
+}
+
+/// Pins the project to its current barème; saves never move an existing pin.
+#[tauri::command]
+pub fn repin_bareme_revision(mut data: serde_json::Value) -> Result<serde_json::Value, String> {
+    bareme_revisions::repin_project(&mut data)?;
+    Ok(data)
+}
+
+#[tauri::command]
+pub fn list_bareme_revisions(bareme_id: String) -> Result<Vec<BaremeRevision>, String> {
+    bareme_revisions::list_revisions(&bareme_id)
+}
+
+#[tauri::command]
+pub fn load_bareme_revision(bareme_id: String, revision: u32) -> Result<serde_json::Value, String> {
+    bareme_revisions::load_revision(&bareme_id, revision)
+}
+
+/// Criteria added, removed, rescaled or renamed between two revisions;
+/// `to_revision` defaults to the latest one.
+#[tauri::command]
+pub fn diff_bareme_revisions(
+    bareme_id: String,
+    from_revision: u32,
+    to_revision: Option<u32>,
+) -> Result<BaremeRevisionDiff, String> {
+    bareme_revisions::diff_revisions(&bareme_id, from_revision, to_revision)
+}
+
 #[tauri::command]
 pub fn delete_bareme(bareme_id: String) -> Result<(), String> {
     baremes::delete_bareme_file(bareme_id)
@@ -66,6 +475,21 @@ pub fn load_baremes() -> Result<Vec<serde_json::Value>, String> {
     baremes::load_baremes_files()
 }
 
+#[tauri::command]
+pub fn list_quarantined_baremes() -> Result<Vec<QuarantinedBareme>, String> {
+    bareme_trash::list_quarantined()
+}
+
+#[tauri::command]
+pub fn restore_quarantined_bareme(entry_id: String) -> Result<serde_json::Value, String> {
+    bareme_trash::restore(&entry_id)
+}
+
+#[tauri::command]
+pub fn purge_quarantined_baremes(entry_ids: Option<Vec<String>>) -> Result<usize, String> {
+    bareme_trash::purge(entry_ids)
+}
+
 #[tauri::command]
 pub fn save_user_settings(data: serde_json::Value) -> Result<(), String> {
     user_settings::save_settings(data)
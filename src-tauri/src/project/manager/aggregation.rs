@@ -0,0 +1,254 @@
+use super::results::{self, ResultsModel};
+use super::types::{
+    AggregatedCategoryScore, AggregatedClip, AggregatedJudgeScore, AggregatedResults,
+    AggregationJudge, AggregationMethod,
+};
+
+const EPSILON: f64 = 1e-9;
+
+/// Judge x clip values in model order, `None` where the judge left the clip
+/// unscored.
+type Matrix = Vec<Vec<Option<f64>>>;
+
+pub fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Mean and population standard deviation.
+pub fn spread(values: &[f64]) -> Option<(f64, f64)> {
+    let mean = results::mean(values)?;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    Some((mean, variance.sqrt()))
+}
+
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let middle = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    })
+}
+
+/// Rank of each value, 1 for the highest; tied values share the mean of the
+/// positions they cover, so two clips tied for first both rank 1.5.
+pub fn fractional_ranks(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let mut order: Vec<(usize, f64)> = values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| value.map(|value| (index, value)))
+        .collect();
+    order.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut ranks = vec![None; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && (order[end].1 - order[start].1).abs() < EPSILON {
+            end += 1;
+        }
+        // Positions start..end are 1-based start+1..=end.
+        let rank = (start + 1 + end) as f64 / 2.0;
+        for (index, _) in &order[start..end] {
+            ranks[*index] = Some(rank);
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// What each judge contributes under the method: points as they are, points
+/// standardised over the judge's own clips, or the judge's ranking.
+fn method_values(points: &Matrix, method: AggregationMethod) -> Matrix {
+    points
+        .iter()
+        .map(|row| match method {
+            AggregationMethod::ZScore => {
+                let scored: Vec<f64> = row.iter().flatten().copied().collect();
+                let Some((mean, std_dev)) = spread(&scored) else {
+                    return row.clone();
+                };
+                row.iter()
+                    .map(|value| {
+                        value.map(|value| {
+                            // A judge who gave every clip the same score
+                            // expresses no preference.
+                            if std_dev < EPSILON {
+                                0.0
+                            } else {
+                                (value - mean) / std_dev
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            AggregationMethod::RankAverage => fractional_ranks(row),
+            _ => row.clone(),
+        })
+        .collect()
+}
+
+/// Combines the judges' values for one clip; returns the score and the judges
+/// the trimmed mean left out.
+fn combine(values: &[(usize, f64)], method: AggregationMethod) -> (Option<f64>, Vec<usize>) {
+    let plain: Vec<f64> = values.iter().map(|(_, value)| *value).collect();
+    match method {
+        AggregationMethod::Median => (median(&plain), Vec::new()),
+        AggregationMethod::TrimmedMean if values.len() >= 3 => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            let lowest = sorted.remove(0).0;
+            let highest = sorted.pop().map(|(judge, _)| judge);
+            let kept: Vec<f64> = sorted.iter().map(|(_, value)| *value).collect();
+            (
+                results::mean(&kept),
+                std::iter::once(lowest).chain(highest).collect(),
+            )
+        }
+        _ => (results::mean(&plain), Vec::new()),
+    }
+}
+
+fn round_for(method: AggregationMethod, value: f64) -> f64 {
+    match method {
+        AggregationMethod::ZScore | AggregationMethod::RankAverage => round_to(value, 3),
+        _ => results::round_score(value),
+    }
+}
+
+/// Aggregates every clip column of `points`, keeping each judge's part.
+fn aggregate_matrix(
+    model: &ResultsModel,
+    points: &Matrix,
+    method: AggregationMethod,
+) -> Vec<(Option<f64>, Vec<AggregatedJudgeScore>)> {
+    let values = method_values(points, method);
+    (0..model.clips.len())
+        .map(|clip| {
+            let scored: Vec<(usize, f64)> = values
+                .iter()
+                .enumerate()
+                .filter_map(|(judge, row)| row[clip].map(|value| (judge, value)))
+                .collect();
+            let (score, dropped) = combine(&scored, method);
+            let judges = model
+                .judges
+                .iter()
+                .enumerate()
+                .map(|(index, judge)| AggregatedJudgeScore {
+                    judge_key: judge.key.clone(),
+                    judge_name: judge.name.clone(),
+                    points: points[index][clip],
+                    value: values[index][clip].map(|value| round_for(method, value)),
+                    dropped: dropped.contains(&index),
+                })
+                .collect();
+            (score.map(|score| round_for(method, score)), judges)
+        })
+        .collect()
+}
+
+/// Combines the scores of the current and imported judges with `method`, for
+/// the total and for each barème category.
+pub fn aggregate(model: &ResultsModel, method: AggregationMethod) -> AggregatedResults {
+    let totals: Matrix = model
+        .judges
+        .iter()
+        .map(|judge| {
+            model
+                .clips
+                .iter()
+                .map(|clip| model.total(judge, &clip.id))
+                .collect()
+        })
+        .collect();
+    let mut categories: Vec<_> = model
+        .categories
+        .iter()
+        .map(|category| {
+            let points: Matrix = model
+                .judges
+                .iter()
+                .map(|judge| {
+                    model
+                        .clips
+                        .iter()
+                        .map(|clip| model.category_score(judge, &clip.id, category))
+                        .collect()
+                })
+                .collect();
+            aggregate_matrix(model, &points, method).into_iter()
+        })
+        .collect();
+
+    let clips = model
+        .clips
+        .iter()
+        .zip(aggregate_matrix(model, &totals, method))
+        .map(|(clip, (score, judges))| AggregatedClip {
+            clip_id: clip.id.clone(),
+            label: clip.label.clone(),
+            contest_category: clip.contest_category.clone(),
+            score,
+            judge_count: judges.iter().filter(|judge| judge.points.is_some()).count(),
+            judges,
+            categories: model
+                .categories
+                .iter()
+                .zip(categories.iter_mut())
+                .filter_map(|(category, scores)| {
+                    let (score, judges) = scores.next()?;
+                    Some(AggregatedCategoryScore {
+                        category: category.name.clone(),
+                        max: category.max,
+                        score,
+                        judges,
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    let judges = model
+        .judges
+        .iter()
+        .zip(&totals)
+        .map(|(judge, row)| {
+            let scored: Vec<f64> = row.iter().flatten().copied().collect();
+            let spread = spread(&scored);
+            AggregationJudge {
+                key: judge.key.clone(),
+                name: judge.name.clone(),
+                scored_clips: scored.len(),
+                mean: spread.map(|(mean, _)| results::round_score(mean)),
+                std_dev: spread.map(|(_, std_dev)| results::round_score(std_dev)),
+            }
+        })
+        .collect();
+
+    AggregatedResults {
+        method,
+        higher_is_better: method != AggregationMethod::RankAverage,
+        total_points: model.total_points,
+        judges,
+        clips,
+    }
+}
+
+pub fn aggregate_results(
+    data: serde_json::Value,
+    method: AggregationMethod,
+) -> Result<AggregatedResults, String> {
+    let model = ResultsModel::from_project(data)?;
+    Ok(aggregate(&model, method))
+}
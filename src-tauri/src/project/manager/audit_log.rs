@@ -0,0 +1,444 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::bareme_revisions;
+use super::csv_export;
+use super::history;
+use super::json_io;
+use super::project_listing;
+use super::project_validation;
+use super::types::{AuditEntry, AuditHead, AuditLog, AuditQuery, AuditVerification};
+
+const HEAD_KEY: &str = "auditHead";
+/// `prev_hash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The log lives next to the project as `<project file stem>.audit.jsonl`,
+/// one entry per line, so it can be shared along with the project file.
+fn log_path(project_path: &Path) -> Result<PathBuf, String> {
+    let stem = project_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| format!("Invalid project path: {}", project_path.display()))?;
+    Ok(project_path.with_file_name(format!("{}.audit.jsonl", stem)))
+}
+
+/// Hash of an entry's content and the previous hash; `hash` itself is left out.
+fn entry_hash(entry: &AuditEntry) -> String {
+    let mut value = serde_json::to_value(entry).unwrap_or_default();
+    if let serde_json::Value::Object(map) = &mut value {
+        map.remove("hash");
+    }
+    Sha256::digest(bareme_revisions::canonical_json(&value).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+type ScoreKey = (Option<String>, String, String);
+
+/// Every criterion value of the project, keyed by (judge, clip, criterion).
+/// The project's own notes have no judge key, so renaming the judge is not
+/// taken for a change of every score.
+fn score_values(data: &serde_json::Value) -> BTreeMap<ScoreKey, serde_json::Value> {
+    let root = project_listing::project_root(data);
+    let judges = std::iter::once((None, &root["notes"])).chain(
+        root["importedJudges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|judge| {
+                (
+                    Some(judge["judgeName"].as_str().unwrap_or("").to_string()),
+                    &judge["notes"],
+                )
+            }),
+    );
+
+    let mut values = BTreeMap::new();
+    for (judge, notes) in judges {
+        let Some(notes) = notes.as_object() else {
+            continue;
+        };
+        for (clip_id, note) in notes {
+            let Some(scores) = note["scores"].as_object() else {
+                continue;
+            };
+            for (criterion_id, score) in scores {
+                values.insert(
+                    (judge.clone(), clip_id.clone(), criterion_id.clone()),
+                    score
+                        .get("value")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null),
+                );
+            }
+        }
+    }
+    values
+}
+
+/// Entries of the log, and the first line that could not be parsed (with the
+/// number of entries read before it). A crash during an append leaves such a
+/// line at the end; it is skipped here and reported by `verify`.
+struct LogContent {
+    entries: Vec<AuditEntry>,
+    unreadable: Option<(u64, usize)>,
+    /// The file does not end with a newline, e.g. after a torn append.
+    open_last_line: bool,
+}
+
+fn read_entries(path: &Path) -> Result<LogContent, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(format!("Failed to read audit log: {}", error)),
+    };
+    let mut content = LogContent {
+        entries: Vec::new(),
+        unreadable: None,
+        open_last_line: !text.is_empty() && !text.ends_with('\n'),
+    };
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => content.entries.push(entry),
+            Err(_) => {
+                content
+                    .unreadable
+                    .get_or_insert((content.entries.len() as u64, index + 1));
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// The saved project as `load_project` sees it: recovered from the `.bak` file
+/// when the project file is damaged, and migrated, so an older file does not
+/// read as changed scores.
+fn read_saved_project(project_path: &Path) -> Result<serde_json::Value, String> {
+    json_io::read_json_with_backup(project_path, "read project", "parse project", |value| {
+        project_validation::normalize_project_value(value, "project file")
+    })
+}
+
+fn set_head(data: &mut serde_json::Value, head: Option<AuditHead>) {
+    let root = match data.get_mut("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => data,
+    };
+    let Some(root) = root.as_object_mut() else {
+        return;
+    };
+    match head.and_then(|head| serde_json::to_value(head).ok()) {
+        Some(head) => {
+            root.insert(HEAD_KEY.to_string(), head);
+        }
+        None => {
+            root.remove(HEAD_KEY);
+        }
+    }
+}
+
+/// Entries appended by `record_changes`, withdrawn again when the project
+/// write they belong to fails.
+pub struct AppendedEntries {
+    path: PathBuf,
+    previous_len: Option<u64>,
+}
+
+impl AppendedEntries {
+    /// Cuts the log back to its length before the append, so a retried save
+    /// does not log the same changes twice.
+    pub fn rollback(self) {
+        let Some(previous_len) = self.previous_len else {
+            return;
+        };
+        let result = OpenOptions::new()
+            .write(true)
+            .open(&self.path)
+            .and_then(|file| file.set_len(previous_len).and_then(|_| file.sync_all()));
+        if let Err(error) = result {
+            eprintln!("[AMV] Failed to roll back audit log: {}", error);
+        }
+    }
+}
+
+/// Appends an entry for every criterion value that differs between the saved
+/// project file and `data`, then records the last entry on `data`, so the
+/// project file vouches for the log it was saved with. Entries are only ever
+/// appended, except for a rollback when the project write fails.
+pub fn record_changes(
+    project_path: &Path,
+    data: &mut serde_json::Value,
+    reason: Option<&str>,
+) -> Result<AppendedEntries, String> {
+    let path = log_path(project_path)?;
+    // A save must still go through when the previous state cannot be read;
+    // its scores are then logged as new.
+    let before = if project_path.is_file() || json_io::backup_path(project_path).is_file() {
+        read_saved_project(project_path).unwrap_or_else(|error| {
+            eprintln!("[AMV] Audit log: previous state unreadable: {}", error);
+            serde_json::Value::Null
+        })
+    } else {
+        serde_json::Value::Null
+    };
+    let before_scores = score_values(&before);
+    let after_scores = score_values(data);
+    let own_judge = project_listing::project_root(data)["project"]["judgeName"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+    let mut clip_names = history::clip_names(&before);
+    clip_names.extend(history::clip_names(data));
+
+    let existing = read_entries(&path)?;
+    if let Some((_, line)) = existing.unreadable {
+        eprintln!(
+            "[AMV] Audit log line {} of {} cannot be read; continuing after it",
+            line,
+            path.display()
+        );
+    }
+    let mut previous = existing.entries.last().cloned();
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let reason = reason
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(str::to_string);
+
+    let keys: BTreeSet<_> = before_scores.keys().chain(after_scores.keys()).collect();
+    let mut lines = String::new();
+    for key in keys {
+        let old_value = before_scores
+            .get(key)
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        let new_value = after_scores
+            .get(key)
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        if old_value == new_value {
+            continue;
+        }
+        let (judge_name, clip_id, criterion_id) = key.clone();
+        let mut entry = AuditEntry {
+            seq: previous.as_ref().map_or(0, |entry| entry.seq + 1),
+            timestamp: timestamp.clone(),
+            judge_name: judge_name.unwrap_or_else(|| own_judge.clone()),
+            clip_name: clip_names
+                .get(&clip_id)
+                .cloned()
+                .unwrap_or_else(|| clip_id.clone()),
+            clip_id,
+            criterion_id,
+            old_value,
+            new_value,
+            reason: reason.clone(),
+            prev_hash: previous
+                .as_ref()
+                .map_or_else(|| GENESIS_HASH.to_string(), |entry| entry.hash.clone()),
+            hash: String::new(),
+        };
+        entry.hash = entry_hash(&entry);
+        lines.push_str(&serde_json::to_string(&entry).map_err(|e| e.to_string())?);
+        lines.push('\n');
+        previous = Some(entry);
+    }
+
+    let mut appended = AppendedEntries {
+        path: path.clone(),
+        previous_len: None,
+    };
+    if !lines.is_empty() {
+        if existing.open_last_line {
+            // Keep a torn line on its own so the new entries stay readable.
+            lines.insert(0, '\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open audit log: {}", e))?;
+        appended.previous_len = Some(
+            file.metadata()
+                .map_err(|e| format!("Failed to open audit log: {}", e))?
+                .len(),
+        );
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write audit log: {}", e))?;
+    }
+
+    set_head(
+        data,
+        previous.map(|entry| AuditHead {
+            entries: entry.seq + 1,
+            hash: entry.hash,
+        }),
+    );
+    Ok(appended)
+}
+
+/// Walks the hash chain and compares its end with the head recorded in the
+/// project file.
+fn verify_entries(log: &LogContent, head: Option<&AuditHead>) -> AuditVerification {
+    let entries = &log.entries;
+    if let Some((index, line)) = log.unreadable {
+        return AuditVerification {
+            valid: false,
+            entries: entries.len() as u64,
+            broken_at: Some(index),
+            message: Some(format!("Line {} of the log cannot be read", line)),
+        };
+    }
+    let mut previous_hash = GENESIS_HASH.to_string();
+    for (index, entry) in entries.iter().enumerate() {
+        let problem = if entry.seq != index as u64 {
+            Some("an entry is missing or out of order")
+        } else if entry.prev_hash != previous_hash {
+            Some("the entry does not follow the previous one")
+        } else if entry.hash != entry_hash(entry) {
+            Some("the entry was modified")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return AuditVerification {
+                valid: false,
+                entries: entries.len() as u64,
+                broken_at: Some(index as u64),
+                message: Some(format!("Entry {}: {}", index, problem)),
+            };
+        }
+        previous_hash = entry.hash.clone();
+    }
+
+    // The log may run ahead of the project when a save failed after the append.
+    let head_problem = head.filter(|head| head.entries > 0).and_then(|head| {
+        let index = head.entries - 1;
+        match entries.get(index as usize) {
+            None => Some((
+                entries.len() as u64,
+                format!(
+                    "The project expects {} entries, the log has {}",
+                    head.entries,
+                    entries.len()
+                ),
+            )),
+            Some(entry) if entry.hash != head.hash => Some((
+                index,
+                format!(
+                    "Entry {} does not match the one recorded in the project",
+                    index
+                ),
+            )),
+            Some(_) => None,
+        }
+    });
+    AuditVerification {
+        valid: head_problem.is_none(),
+        entries: entries.len() as u64,
+        broken_at: head_problem.as_ref().map(|(index, _)| *index),
+        message: head_problem.map(|(_, message)| message),
+    }
+}
+
+fn load(file_path: &str) -> Result<(Vec<AuditEntry>, AuditVerification), String> {
+    let project_path = Path::new(file_path);
+    let data = read_saved_project(project_path)?;
+    let head: Option<AuditHead> = project_listing::project_root(&data)
+        .get(HEAD_KEY)
+        .and_then(|head| serde_json::from_value(head.clone()).ok());
+    let log = read_entries(&log_path(project_path)?)?;
+    let verification = verify_entries(&log, head.as_ref());
+    Ok((log.entries, verification))
+}
+
+fn matches(entry: &AuditEntry, query: &AuditQuery) -> bool {
+    let same = |filter: &Option<String>, value: &str| {
+        filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|filter| !filter.is_empty())
+            .map_or(true, |filter| filter.eq_ignore_ascii_case(value.trim()))
+    };
+    same(&query.clip_id, &entry.clip_id)
+        && same(&query.criterion_id, &entry.criterion_id)
+        && same(&query.judge_name, &entry.judge_name)
+}
+
+/// The project's score changes, oldest first, filtered by clip, criterion
+/// and judge, with the verification of the whole chain.
+pub fn query(file_path: &str, query: &AuditQuery) -> Result<AuditLog, String> {
+    let (entries, verification) = load(file_path)?;
+    Ok(AuditLog {
+        entries: entries
+            .into_iter()
+            .filter(|entry| matches(entry, query))
+            .collect(),
+        verification,
+    })
+}
+
+pub fn verify(file_path: &str) -> Result<AuditVerification, String> {
+    Ok(load(file_path)?.1)
+}
+
+fn value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Number(number) => number
+            .as_f64()
+            .map_or_else(|| number.to_string(), |number| number.to_string()),
+        other => other.to_string(),
+    }
+}
+
+pub fn export_csv(file_path: &str, output_path: &str, query: &AuditQuery) -> Result<(), String> {
+    let log = self::query(file_path, query)?;
+    let header = [
+        "seq",
+        "timestamp",
+        "judge",
+        "clip",
+        "clip_id",
+        "criterion_id",
+        "old_value",
+        "new_value",
+        "reason",
+        "prev_hash",
+        "hash",
+    ];
+    let rows: Vec<Vec<String>> = std::iter::once(header.iter().map(|h| h.to_string()).collect())
+        .chain(log.entries.iter().map(|entry| {
+            vec![
+                entry.seq.to_string(),
+                entry.timestamp.clone(),
+                entry.judge_name.clone(),
+                entry.clip_name.clone(),
+                entry.clip_id.clone(),
+                entry.criterion_id.clone(),
+                value_text(&entry.old_value),
+                value_text(&entry.new_value),
+                entry.reason.clone().unwrap_or_default(),
+                entry.prev_hash.clone(),
+                entry.hash.clone(),
+            ]
+        }))
+        .collect();
+    json_io::write_atomic(
+        Path::new(output_path),
+        csv_export::build_table(&rows).as_bytes(),
+        "export audit log CSV",
+    )
+}
@@ -0,0 +1,378 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::baremes;
+use super::project_search::fold;
+use super::spreadsheet::{self, SheetRows};
+use super::types::ImportedSheetBareme;
+
+/// Header rows sit at the top of the sheet; past this the scan gives up.
+const MAX_HEADER_SCAN_ROWS: usize = 10;
+/// Official grids score in half points; used when no score is there to guess from.
+const DEFAULT_STEP: f64 = 0.5;
+const STEP_CANDIDATES: &[f64] = &[1.0, 0.5, 0.25, 0.1];
+const EPSILON: f64 = 1e-6;
+/// Same palette as the official barème categories.
+const CATEGORY_COLORS: &[&str] = &["#fb923c", "#a78bfa", "#34d399", "#f59e0b", "#60a5fa"];
+const IMPORTED_DESCRIPTION: &str = "Barème détecté automatiquement depuis le fichier importé.";
+
+#[derive(Debug, Clone)]
+pub struct HeaderCriterion {
+    pub column: usize,
+    pub name: String,
+    pub max: f64,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HeaderCategory {
+    pub column: usize,
+    pub name: String,
+    pub max: f64,
+}
+
+/// Grid encoded in the header of a contest sheet: an optional category row
+/// ("EDITING /24") above the criteria row ("Rhythm and Synchronization /12").
+#[derive(Debug, Clone)]
+pub struct SheetHeader {
+    /// Row of the criteria labels; scores start on the next row.
+    pub criteria_row: usize,
+    pub criteria: Vec<HeaderCriterion>,
+    pub categories: Vec<HeaderCategory>,
+    /// From a `Judge: <name>` / `Juges : <name>` cell in the header.
+    pub judge_name: Option<String>,
+}
+
+/// Splits `"Scene Selection /12"` into its name and maximum.
+fn split_label(value: &str) -> Option<(String, f64)> {
+    let (name, max) = value.rsplit_once('/')?;
+    let max = spreadsheet::parse_decimal(max).filter(|max| *max > 0.0)?;
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = name
+        .trim_end_matches(['/', '-', ':'])
+        .trim_end()
+        .to_string();
+    (!name.is_empty()).then_some((name, max))
+}
+
+fn judge_cell(value: &str) -> Option<String> {
+    let (label, name) = value.split_once(':')?;
+    let label = fold(label);
+    if !matches!(
+        label.as_str(),
+        "judge" | "judges" | "juge" | "juges" | "jury"
+    ) {
+        return None;
+    }
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// First contiguous run of `name /max` cells in a row, without `TOTAL`.
+fn criteria_run(row: &[String]) -> Vec<(usize, String, f64)> {
+    let Some(start) =
+        (0..row.len()).find(|&column| split_label(spreadsheet::cell(row, column)).is_some())
+    else {
+        return Vec::new();
+    };
+    (start..row.len())
+        .map_while(|column| {
+            split_label(spreadsheet::cell(row, column)).map(|(name, max)| (column, name, max))
+        })
+        .filter(|(_, name, _)| fold(name) != "total")
+        .collect()
+}
+
+/// Categories cover the criteria to their right until their points are used
+/// up or the next category starts. Criteria left over after a full category,
+/// such as "ENCODING & MIXING /6", are categories of their own.
+fn assign_categories(criteria: &mut [HeaderCriterion], categories: &mut Vec<HeaderCategory>) {
+    if categories.is_empty() {
+        return;
+    }
+    for (index, category) in categories.iter().enumerate() {
+        let end = categories
+            .get(index + 1)
+            .map_or(usize::MAX, |next| next.column);
+        let mut used = 0.0;
+        for criterion in criteria
+            .iter_mut()
+            .filter(|criterion| criterion.column >= category.column && criterion.column < end)
+        {
+            if used + EPSILON >= category.max {
+                break;
+            }
+            used += criterion.max;
+            criterion.category = Some(category.name.clone());
+        }
+    }
+    for criterion in criteria.iter_mut().filter(|c| c.category.is_none()) {
+        criterion.category = Some(criterion.name.clone());
+        categories.push(HeaderCategory {
+            column: criterion.column,
+            name: criterion.name.clone(),
+            max: criterion.max,
+        });
+    }
+    categories.sort_by_key(|category| category.column);
+}
+
+pub fn detect_header(rows: &SheetRows) -> Option<SheetHeader> {
+    let scan = rows.len().min(MAX_HEADER_SCAN_ROWS);
+    let (criteria_row, run) = (0..scan)
+        .map(|index| (index, criteria_run(&rows[index])))
+        .find(|(_, run)| run.len() >= 2)?;
+
+    let mut criteria: Vec<HeaderCriterion> = run
+        .into_iter()
+        .map(|(column, name, max)| HeaderCriterion {
+            column,
+            name,
+            max,
+            category: None,
+        })
+        .collect();
+
+    let first_column = criteria.first().map_or(0, |criterion| criterion.column);
+    let mut categories: Vec<HeaderCategory> = criteria_row
+        .checked_sub(1)
+        .map(|index| &rows[index])
+        .map(|row| {
+            (first_column..row.len())
+                .filter_map(|column| {
+                    let (name, max) = split_label(spreadsheet::cell(row, column))?;
+                    Some(HeaderCategory { column, name, max })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    assign_categories(&mut criteria, &mut categories);
+
+    let judge_name = rows[..=criteria_row]
+        .iter()
+        .flat_map(|row| row.iter())
+        .find_map(|value| judge_cell(value));
+
+    Some(SheetHeader {
+        criteria_row,
+        criteria,
+        categories,
+        judge_name,
+    })
+}
+
+/// Score rows under the header, up to the first row with no score at all;
+/// summary tables further down the sheet are left out.
+pub fn score_rows<'a>(rows: &'a SheetRows, header: &SheetHeader) -> &'a [Vec<String>] {
+    let start = (header.criteria_row + 1).min(rows.len());
+    let block = &rows[start..];
+    let end = block
+        .iter()
+        .position(|row| {
+            header
+                .criteria
+                .iter()
+                .all(|criterion| spreadsheet::cell(row, criterion.column).is_empty())
+        })
+        .unwrap_or(block.len());
+    &block[..end]
+}
+
+fn is_multiple(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() < EPSILON * steps.abs().max(1.0)
+}
+
+/// Coarsest step that every maximum and every score of the sheet fit on.
+/// Judges use one step for the whole grid, so a single quarter point in any
+/// column makes the whole grid quarter points.
+fn guess_step(rows: &SheetRows, header: &SheetHeader) -> f64 {
+    let scored = score_rows(rows, header);
+    let scores: Vec<f64> = header
+        .criteria
+        .iter()
+        .flat_map(|criterion| {
+            scored
+                .iter()
+                .filter_map(|row| {
+                    spreadsheet::parse_decimal(spreadsheet::cell(row, criterion.column))
+                })
+                .filter(|score| (0.0..=criterion.max).contains(score))
+        })
+        .collect();
+    let fits = |step: f64| {
+        header
+            .criteria
+            .iter()
+            .all(|criterion| is_multiple(criterion.max, step))
+            && scores.iter().all(|score| is_multiple(*score, step))
+    };
+    if scores.is_empty() && fits(DEFAULT_STEP) {
+        return DEFAULT_STEP;
+    }
+    STEP_CANDIDATES
+        .iter()
+        .copied()
+        .find(|step| fits(*step))
+        .unwrap_or(0.01)
+}
+
+pub(super) fn slug(value: &str) -> String {
+    let mut out = String::new();
+    for ch in fold(value).chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch);
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    let out = out.trim_matches('-').to_string();
+    if out.is_empty() {
+        "criterion".to_string()
+    } else {
+        out
+    }
+}
+
+/// "JE 2025 Sheet_Lightning - Feuille 1" -> "JE 2025 Sheet_Lightning".
+fn bareme_name_from_path(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match stem.rsplit_once(" - ") {
+        Some((name, sheet))
+            if ["feuille", "sheet"]
+                .iter()
+                .any(|prefix| fold(sheet).starts_with(prefix)) =>
+        {
+            name.trim().to_string()
+        }
+        _ => stem.trim().to_string(),
+    }
+}
+
+fn round_points(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn build_bareme(
+    rows: &SheetRows,
+    header: &SheetHeader,
+    name: &str,
+) -> (serde_json::Value, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut ids = HashSet::new();
+    let step = guess_step(rows, header);
+    let criteria: Vec<serde_json::Value> = header
+        .criteria
+        .iter()
+        .map(|criterion| {
+            let base = slug(&criterion.name);
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while !ids.insert(id.clone()) {
+                id = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            let mut value = serde_json::json!({
+                "id": id,
+                "name": criterion.name,
+                "type": "numeric",
+                "min": 0,
+                "max": criterion.max,
+                "step": step,
+                "required": true,
+            });
+            if let Some(category) = &criterion.category {
+                value["category"] = serde_json::Value::String(category.clone());
+            }
+            value
+        })
+        .collect();
+
+    for category in &header.categories {
+        let sum: f64 = header
+            .criteria
+            .iter()
+            .filter(|criterion| criterion.category.as_deref() == Some(category.name.as_str()))
+            .map(|criterion| criterion.max)
+            .sum();
+        if (sum - category.max).abs() > EPSILON {
+            warnings.push(format!(
+                "{} is marked /{} but its criteria add up to {}",
+                category.name,
+                category.max,
+                round_points(sum)
+            ));
+        }
+    }
+
+    let category_colors: serde_json::Map<String, serde_json::Value> = header
+        .categories
+        .iter()
+        .enumerate()
+        .map(|(index, category)| {
+            let color = CATEGORY_COLORS[index % CATEGORY_COLORS.len()];
+            (
+                category.name.clone(),
+                serde_json::Value::String(color.to_string()),
+            )
+        })
+        .collect();
+    let total: f64 = header.criteria.iter().map(|criterion| criterion.max).sum();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let mut bareme = serde_json::json!({
+        "id": format!("imported-{}", uuid::Uuid::new_v4()),
+        "name": name,
+        "description": IMPORTED_DESCRIPTION,
+        "isOfficial": false,
+        "criteria": criteria,
+        "totalPoints": round_points(total),
+        "createdAt": now,
+        "updatedAt": now,
+    });
+    if !category_colors.is_empty() {
+        bareme["categoryColors"] = serde_json::Value::Object(category_colors);
+    }
+    (bareme, warnings)
+}
+
+/// Turns the header of a contest sheet into a barème and saves it, unless the
+/// folder already has the same grid under the auto-import fingerprint, in
+/// which case that barème is returned instead.
+pub fn import_bareme_from_sheet(
+    file_path: &str,
+    name: Option<String>,
+) -> Result<ImportedSheetBareme, String> {
+    let path = Path::new(file_path);
+    let rows = spreadsheet::read_rows(path)?;
+    let header = detect_header(&rows).ok_or_else(|| {
+        "No barème header found: expected a row of \"Criterion /max\" cells".to_string()
+    })?;
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| bareme_name_from_path(path));
+    let (bareme, warnings) = build_bareme(&rows, &header, &name);
+
+    if let Some(existing) = baremes::find_duplicate(&bareme) {
+        let existing_id = existing["id"].as_str().map(str::to_string);
+        return Ok(ImportedSheetBareme {
+            bareme: existing,
+            duplicate_of: existing_id,
+            judge_name: header.judge_name,
+            warnings,
+        });
+    }
+
+    let bareme_id = bareme["id"].as_str().unwrap_or_default().to_string();
+    baremes::save_bareme_file(bareme.clone(), bareme_id.clone())?;
+    Ok(ImportedSheetBareme {
+        bareme: baremes::find_bareme(&bareme_id).unwrap_or(bareme),
+        duplicate_of: None,
+        judge_name: header.judge_name,
+        warnings,
+    })
+}
@@ -0,0 +1,424 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::baremes;
+use super::paths;
+use super::project_listing;
+use super::types::{
+    BaremeCriterionChange, BaremeRevision, BaremeRevisionDiff, BaremeRevisionPin,
+    BaremeRevisionPinStatus,
+};
+
+const REVISIONS_FOLDER_NAME: &str = ".revisions";
+const REVISION_KEY: &str = "revision";
+const CONTENT_HASH_KEY: &str = "contentHash";
+const PIN_KEY: &str = "baremeRevision";
+/// Criterion fields that change how a score reads. Descriptions, colors and
+/// timestamps can be edited without creating a new revision.
+const SCORED_CRITERION_KEYS: &[&str] = &[
+    "id", "name", "type", "min", "max", "step", "options", "category",
+];
+
+/// Revisions of a barème live in `Baremes/.revisions/<id>/r0001.json`, one
+/// immutable file per revision.
+fn revisions_folder(bareme_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::baremes_folder()?
+        .join(REVISIONS_FOLDER_NAME)
+        .join(paths::sanitize_bareme_file_name(bareme_id)))
+}
+
+fn revision_path(folder: &Path, revision: u32) -> PathBuf {
+    folder.join(format!("r{:04}.json", revision))
+}
+
+/// Revision numbers stored for a barème, ascending.
+fn revision_numbers(folder: &Path) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut numbers: Vec<u32> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix('r')?
+                .strip_suffix(".json")?
+                .parse::<u32>()
+                .ok()
+        })
+        .collect();
+    numbers.sort_unstable();
+    numbers
+}
+
+fn read_revision(folder: &Path, revision: u32) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(revision_path(folder, revision)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Serializes with sorted keys at every level, so the hash does not depend on
+/// the key order the frontend happened to use.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        serde_json::Value::String(key.clone()),
+                        canonical_json(&map[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Hash of what a score means under this barème: the scored fields of every
+/// criterion and the total.
+pub fn content_hash(bareme: &serde_json::Value) -> String {
+    let criteria: Vec<serde_json::Value> = bareme["criteria"]
+        .as_array()
+        .map(|criteria| {
+            criteria
+                .iter()
+                .map(|criterion| {
+                    let fields: serde_json::Map<String, serde_json::Value> = SCORED_CRITERION_KEYS
+                        .iter()
+                        .filter_map(|key| {
+                            let value = criterion.get(*key)?;
+                            (!value.is_null()).then(|| (key.to_string(), value.clone()))
+                        })
+                        .collect();
+                    serde_json::Value::Object(fields)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let scored = serde_json::json!({
+        "criteria": criteria,
+        "totalPoints": bareme["totalPoints"],
+    });
+
+    Sha256::digest(canonical_json(&scored).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn write_revision(folder: &Path, revision: u32, bareme: &serde_json::Value) -> Result<(), String> {
+    fs::create_dir_all(folder).map_err(|e| format!("Failed to create revisions folder: {}", e))?;
+    let json = serde_json::to_string_pretty(bareme).map_err(|e| e.to_string())?;
+    // `create_new`: a published revision is never rewritten.
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(revision_path(folder, revision))
+        .map_err(|e| format!("Failed to write bareme revision {}: {}", revision, e))?;
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write bareme revision {}: {}", revision, e))
+}
+
+fn stamp(bareme: &mut serde_json::Value, revision: u32, hash: &str) {
+    if let serde_json::Value::Object(map) = bareme {
+        map.insert(REVISION_KEY.to_string(), serde_json::json!(revision));
+        map.insert(
+            CONTENT_HASH_KEY.to_string(),
+            serde_json::Value::String(hash.to_string()),
+        );
+    }
+}
+
+/// Stamps `data` with its revision number and content hash before it is saved.
+/// A save that changes the scored content appends a new revision; one that
+/// only touches descriptions or colors keeps the current revision.
+/// A barème saved before revisions existed becomes revision 1 first, so the
+/// version earlier projects were scored with is kept.
+pub fn record_revision(data: &mut serde_json::Value, bareme_id: &str) -> Result<u32, String> {
+    let folder = revisions_folder(bareme_id)?;
+    let hash = content_hash(data);
+    let mut numbers = revision_numbers(&folder);
+
+    if numbers.is_empty() {
+        if let Some(mut previous) = baremes::find_bareme(bareme_id) {
+            let previous_hash = content_hash(&previous);
+            if previous_hash != hash {
+                stamp(&mut previous, 1, &previous_hash);
+                write_revision(&folder, 1, &previous)?;
+                numbers.push(1);
+            }
+        }
+    }
+
+    if let Some(&latest) = numbers.last() {
+        let latest_hash = read_revision(&folder, latest)
+            .map(|revision| content_hash(&revision))
+            .unwrap_or_default();
+        if latest_hash == hash {
+            stamp(data, latest, &hash);
+            return Ok(latest);
+        }
+    }
+
+    let revision = numbers.last().map_or(1, |latest| latest + 1);
+    stamp(data, revision, &hash);
+    let mut published = data.clone();
+    if let serde_json::Value::Object(map) = &mut published {
+        map.insert(
+            "revisedAt".to_string(),
+            serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    write_revision(&folder, revision, &published)?;
+    Ok(revision)
+}
+
+pub fn list_revisions(bareme_id: &str) -> Result<Vec<BaremeRevision>, String> {
+    let folder = revisions_folder(bareme_id)?;
+    let mut revisions: Vec<BaremeRevision> = revision_numbers(&folder)
+        .into_iter()
+        .filter_map(|revision| {
+            let value = read_revision(&folder, revision)?;
+            Some(BaremeRevision {
+                revision,
+                content_hash: content_hash(&value),
+                name: value["name"].as_str().unwrap_or("").to_string(),
+                revised_at: value["revisedAt"]
+                    .as_str()
+                    .or_else(|| value["updatedAt"].as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                total_points: value["totalPoints"].as_f64().unwrap_or(0.0),
+                criteria_count: value["criteria"].as_array().map_or(0, Vec::len),
+            })
+        })
+        .collect();
+    revisions.reverse();
+    Ok(revisions)
+}
+
+pub fn load_revision(bareme_id: &str, revision: u32) -> Result<serde_json::Value, String> {
+    let folder = revisions_folder(bareme_id)?;
+    read_revision(&folder, revision)
+        .ok_or_else(|| format!("Revision {} of bareme {} not found", revision, bareme_id))
+}
+
+fn revision_for_hash(bareme_id: &str, hash: &str) -> Option<u32> {
+    let folder = revisions_folder(bareme_id).ok()?;
+    revision_numbers(&folder)
+        .into_iter()
+        .rev()
+        .find(|revision| {
+            read_revision(&folder, *revision).is_some_and(|value| content_hash(&value) == hash)
+        })
+}
+
+fn project_bareme_id(root: &serde_json::Value) -> Option<String> {
+    root["baremeId"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+fn existing_pin(root: &serde_json::Value, bareme_id: &str) -> Option<BaremeRevisionPin> {
+    root.get(PIN_KEY)
+        .and_then(|pin| serde_json::from_value(pin.clone()).ok())
+        .filter(|pin: &BaremeRevisionPin| pin.bareme_id == bareme_id)
+}
+
+/// The revision the project would be pinned to now: its embedded barème when
+/// there is one, otherwise the latest stored revision.
+fn current_pin(root: &serde_json::Value, bareme_id: &str) -> Option<BaremeRevisionPin> {
+    let embedded = Some(&root["bareme"]).filter(|bareme| bareme["id"].as_str() == Some(bareme_id));
+    match embedded {
+        Some(bareme) => {
+            let hash = content_hash(bareme);
+            let stamped = bareme[REVISION_KEY]
+                .as_u64()
+                .map(|revision| revision as u32)
+                .filter(|_| bareme[CONTENT_HASH_KEY].as_str() == Some(hash.as_str()));
+            Some(BaremeRevisionPin {
+                revision: stamped.or_else(|| revision_for_hash(bareme_id, &hash)),
+                bareme_id: bareme_id.to_string(),
+                content_hash: hash,
+            })
+        }
+        None => {
+            let latest = list_revisions(bareme_id).ok()?.into_iter().next()?;
+            Some(BaremeRevisionPin {
+                bareme_id: bareme_id.to_string(),
+                revision: Some(latest.revision),
+                content_hash: latest.content_hash,
+            })
+        }
+    }
+}
+
+fn set_pin(root: &mut serde_json::Value, pin: BaremeRevisionPin) {
+    if let (serde_json::Value::Object(map), Ok(pin)) = (root, serde_json::to_value(pin)) {
+        map.insert(PIN_KEY.to_string(), pin);
+    }
+}
+
+/// Records on a project which barème revision its scores were given with.
+/// An existing pin is kept even when the barème changed since, so scores keep
+/// pointing at the grid they were given with; see `pin_status` and
+/// `repin_project`. A missing pin points at the current barème.
+pub fn pin_project(data: &mut serde_json::Value) {
+    let root = match data.get_mut("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => data,
+    };
+    let Some(bareme_id) = project_bareme_id(root) else {
+        return;
+    };
+    if existing_pin(root, &bareme_id).is_some() {
+        return;
+    }
+    if let Some(pin) = current_pin(root, &bareme_id) {
+        set_pin(root, pin);
+    }
+}
+
+/// Moves the pin to the current barème, when the user accepts scoring the
+/// project against it.
+pub fn repin_project(data: &mut serde_json::Value) -> Result<(), String> {
+    let root = match data.get_mut("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => data,
+    };
+    let bareme_id = project_bareme_id(root).ok_or("The project has no barème")?;
+    let pin = current_pin(root, &bareme_id)
+        .ok_or_else(|| format!("Bareme {} has no revisions", bareme_id))?;
+    set_pin(root, pin);
+    Ok(())
+}
+
+/// The project's pin next to the current barème, so a changed grid can be
+/// reported instead of silently re-pinning.
+pub fn pin_status(data: &serde_json::Value) -> BaremeRevisionPinStatus {
+    let root = project_listing::project_root(data);
+    let bareme_id = project_bareme_id(root);
+    let pinned = bareme_id
+        .as_deref()
+        .and_then(|bareme_id| existing_pin(root, bareme_id));
+    let current = bareme_id
+        .as_deref()
+        .and_then(|bareme_id| current_pin(root, bareme_id));
+    let mismatch = match (&pinned, &current) {
+        (Some(pinned), Some(current)) => pinned.content_hash != current.content_hash,
+        _ => false,
+    };
+    BaremeRevisionPinStatus {
+        pinned,
+        current,
+        mismatch,
+    }
+}
+
+fn criterion_key(criterion: &serde_json::Value) -> String {
+    criterion["id"].as_str().unwrap_or("").to_string()
+}
+
+fn criterion_name(criterion: &serde_json::Value) -> String {
+    criterion["name"].as_str().unwrap_or("").trim().to_string()
+}
+
+fn criterion_change(
+    from: Option<&serde_json::Value>,
+    to: Option<&serde_json::Value>,
+) -> BaremeCriterionChange {
+    let either = to.or(from).cloned().unwrap_or_default();
+    BaremeCriterionChange {
+        criterion_id: criterion_key(&either),
+        name: criterion_name(&either),
+        previous_name: from.map(criterion_name),
+        from_min: from.and_then(|c| c["min"].as_f64()),
+        from_max: from.and_then(|c| c["max"].as_f64()),
+        from_step: from.and_then(|c| c["step"].as_f64()),
+        to_min: to.and_then(|c| c["min"].as_f64()),
+        to_max: to.and_then(|c| c["max"].as_f64()),
+        to_step: to.and_then(|c| c["step"].as_f64()),
+    }
+}
+
+/// Criteria added, removed, rescaled (min, max or step changed) or renamed
+/// between two revisions. Criteria are matched by id, then by name for ids
+/// regenerated by an import.
+pub fn diff_revisions(
+    bareme_id: &str,
+    from_revision: u32,
+    to_revision: Option<u32>,
+) -> Result<BaremeRevisionDiff, String> {
+    let to_revision = match to_revision {
+        Some(revision) => revision,
+        None => revision_numbers(&revisions_folder(bareme_id)?)
+            .last()
+            .copied()
+            .ok_or_else(|| format!("Bareme {} has no revisions", bareme_id))?,
+    };
+    let from = load_revision(bareme_id, from_revision)?;
+    let to = load_revision(bareme_id, to_revision)?;
+    let from_criteria = from["criteria"].as_array().cloned().unwrap_or_default();
+    let to_criteria = to["criteria"].as_array().cloned().unwrap_or_default();
+
+    let mut matched_from = HashSet::new();
+    let mut diff = BaremeRevisionDiff {
+        bareme_id: bareme_id.to_string(),
+        from_revision,
+        to_revision,
+        from_total_points: from["totalPoints"].as_f64().unwrap_or(0.0),
+        to_total_points: to["totalPoints"].as_f64().unwrap_or(0.0),
+        added: Vec::new(),
+        removed: Vec::new(),
+        rescaled: Vec::new(),
+        renamed: Vec::new(),
+    };
+
+    for criterion in &to_criteria {
+        let by_id = from_criteria.iter().position(|old| {
+            !criterion_key(old).is_empty() && criterion_key(old) == criterion_key(criterion)
+        });
+        let by_name = || {
+            from_criteria.iter().enumerate().position(|(index, old)| {
+                !matched_from.contains(&index)
+                    && criterion_name(old).to_lowercase()
+                        == criterion_name(criterion).to_lowercase()
+            })
+        };
+        let Some(index) = by_id.or_else(by_name) else {
+            diff.added.push(criterion_change(None, Some(criterion)));
+            continue;
+        };
+        matched_from.insert(index);
+        let old = &from_criteria[index];
+        let change = criterion_change(Some(old), Some(criterion));
+        if change.from_min != change.to_min
+            || change.from_max != change.to_max
+            || change.from_step != change.to_step
+        {
+            diff.rescaled.push(change.clone());
+        }
+        if criterion_name(old) != criterion_name(criterion) {
+            diff.renamed.push(change);
+        }
+    }
+    for (index, old) in from_criteria.iter().enumerate() {
+        if !matched_from.contains(&index) {
+            diff.removed.push(criterion_change(Some(old), None));
+        }
+    }
+
+    Ok(diff)
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::file_watch;
+use super::json_io;
+use super::paths;
+use super::types::QuarantinedBareme;
+
+const TRASH_FOLDER_NAME: &str = ".trash";
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// `.trash/index.json`: why each file was set aside, plus the file names the
+/// user restored, which the automatic dedup must leave alone from then on.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TrashIndex {
+    entries: Vec<TrashEntry>,
+    kept_file_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashEntry {
+    id: String,
+    original_file_name: String,
+    reason: String,
+    quarantined_at: String,
+}
+
+fn trash_folder() -> Result<PathBuf, String> {
+    Ok(paths::baremes_folder()?.join(TRASH_FOLDER_NAME))
+}
+
+fn read_index(trash: &Path) -> TrashIndex {
+    fs::read_to_string(trash.join(INDEX_FILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(trash: &Path, index: &TrashIndex) -> Result<(), String> {
+    paths::ensure_directory_exists(trash)?;
+    let value = serde_json::to_value(index).map_err(|e| e.to_string())?;
+    json_io::write_pretty_json(
+        &trash.join(INDEX_FILE_NAME),
+        &value,
+        "write bareme trash index",
+    )
+}
+
+/// True when the user restored this file from the trash; such files are never
+/// quarantined again by the automatic dedup.
+pub fn is_kept(path: &Path) -> bool {
+    let Ok(trash) = trash_folder() else {
+        return false;
+    };
+    let Some(name) = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+    else {
+        return false;
+    };
+    read_index(&trash).kept_file_names.contains(&name)
+}
+
+/// Moves a barème file to `Baremes/.trash` instead of deleting it.
+pub fn quarantine(path: &Path, reason: &str) -> Result<(), String> {
+    let trash = trash_folder()?;
+    paths::ensure_directory_exists(&trash)?;
+
+    let original_file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let id = format!(
+        "{}__{}",
+        chrono::Local::now().format("%Y%m%d%H%M%S%3f"),
+        original_file_name
+    );
+
+    file_watch::record_removal(path);
+    fs::rename(path, trash.join(&id))
+        .map_err(|e| format!("Failed to move {} to the trash: {}", path.display(), e))?;
+    eprintln!(
+        "[AMV] Quarantined bareme {}: {}",
+        original_file_name, reason
+    );
+
+    let mut index = read_index(&trash);
+    index
+        .kept_file_names
+        .retain(|name| *name != original_file_name);
+    index.entries.push(TrashEntry {
+        id,
+        original_file_name,
+        reason: reason.to_string(),
+        quarantined_at: chrono::Utc::now().to_rfc3339(),
+    });
+    write_index(&trash, &index)
+}
+
+fn summarize(trash: &Path, entry: &TrashEntry) -> Option<QuarantinedBareme> {
+    let path = trash.join(&entry.id);
+    let metadata = fs::metadata(&path).ok()?;
+    let value = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .unwrap_or_default();
+    Some(QuarantinedBareme {
+        id: entry.id.clone(),
+        original_file_name: entry.original_file_name.clone(),
+        bareme_id: value["id"].as_str().unwrap_or("").to_string(),
+        bareme_name: value["name"].as_str().unwrap_or("").to_string(),
+        reason: entry.reason.clone(),
+        quarantined_at: entry.quarantined_at.clone(),
+        size_bytes: metadata.len(),
+    })
+}
+
+/// Quarantined barèmes, newest first. Index entries whose file was removed by
+/// hand are skipped.
+pub fn list_quarantined() -> Result<Vec<QuarantinedBareme>, String> {
+    let trash = trash_folder()?;
+    let index = read_index(&trash);
+    let mut items: Vec<QuarantinedBareme> = index
+        .entries
+        .iter()
+        .filter_map(|entry| summarize(&trash, entry))
+        .collect();
+    items.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at));
+    Ok(items)
+}
+
+fn free_restore_path(folder: &Path, file_name: &str) -> PathBuf {
+    let candidate = folder.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "bareme".to_string());
+    (2..)
+        .map(|n| folder.join(format!("{} ({}).json", stem, n)))
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+/// Moves a quarantined barème back under its original file name (suffixed if
+/// taken) and returns its content.
+pub fn restore(entry_id: &str) -> Result<serde_json::Value, String> {
+    let trash = trash_folder()?;
+    let mut index = read_index(&trash);
+    let position = index
+        .entries
+        .iter()
+        .position(|entry| entry.id == entry_id)
+        .ok_or_else(|| format!("Quarantined bareme not found: {}", entry_id))?;
+    let entry = index.entries[position].clone();
+
+    let source = trash.join(&entry.id);
+    let content = fs::read_to_string(&source)
+        .map_err(|e| format!("Failed to read quarantined bareme: {}", e))?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse quarantined bareme: {}", e))?;
+
+    let folder = paths::baremes_folder()?;
+    let target = free_restore_path(&folder, &entry.original_file_name);
+    file_watch::record_write(&target, content.as_bytes());
+    fs::rename(&source, &target).map_err(|e| format!("Failed to restore bareme: {}", e))?;
+
+    index.entries.remove(position);
+    if let Some(name) = target.file_name() {
+        index
+            .kept_file_names
+            .push(name.to_string_lossy().to_string());
+    }
+    write_index(&trash, &index)?;
+    Ok(value)
+}
+
+/// Deletes quarantined barèmes for good: the given ids, or every entry when
+/// `entry_ids` is `None`. Returns the number of files removed.
+pub fn purge(entry_ids: Option<Vec<String>>) -> Result<usize, String> {
+    let trash = trash_folder()?;
+    let mut index = read_index(&trash);
+    let (purged, kept): (Vec<TrashEntry>, Vec<TrashEntry>) =
+        index.entries.drain(..).partition(|entry| {
+            entry_ids
+                .as_ref()
+                .map_or(true, |ids| ids.contains(&entry.id))
+        });
+
+    let mut remaining = kept;
+    let mut errors = Vec::new();
+    let mut removed = 0;
+    for entry in purged {
+        match fs::remove_file(trash.join(&entry.id)) {
+            Ok(()) => removed += 1,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                errors.push(format!("{}: {}", entry.id, error));
+                remaining.push(entry);
+            }
+        }
+    }
+
+    index.entries = remaining;
+    write_index(&trash, &index)?;
+    if errors.is_empty() {
+        Ok(removed)
+    } else {
+        Err(format!("Failed to purge {}", errors.join("; ")))
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+
+use super::project_search::fold;
+use super::types::{Bareme, BaremeValidationIssue, Criterion, TieBreaker};
+
+const MAX_REPORTED_ISSUES: usize = 8;
+const CRITERION_TYPES: &[&str] = &["numeric", "slider", "boolean", "select", "text"];
+const RULE_TYPES: &[&str] = &["min", "max", "required", "step"];
+/// Tolerance for float rounding when checking that a step divides a range.
+const EPSILON: f64 = 1e-6;
+const TOTAL_TOLERANCE: f64 = 0.01;
+
+fn issue(
+    path: impl Into<String>,
+    criterion_id: Option<&str>,
+    message: impl Into<String>,
+) -> BaremeValidationIssue {
+    BaremeValidationIssue {
+        path: path.into(),
+        criterion_id: criterion_id.filter(|id| !id.is_empty()).map(str::to_string),
+        message: message.into(),
+    }
+}
+
+/// Deserializes a raw barème through the typed model. Type errors are reported
+/// with the JSON path of the offending value.
+pub fn parse_bareme(value: serde_json::Value) -> Result<Bareme, Vec<BaremeValidationIssue>> {
+    let bareme: Bareme = serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = error.path().to_string();
+        vec![issue(
+            if path == "." { String::new() } else { path },
+            None,
+            error.into_inner().to_string(),
+        )]
+    })?;
+
+    let issues = validate_bareme(&bareme);
+    if issues.is_empty() {
+        Ok(bareme)
+    } else {
+        Err(issues)
+    }
+}
+
+fn is_scored(criterion: &Criterion) -> bool {
+    matches!(criterion.kind.as_str(), "numeric" | "slider")
+}
+
+/// Whether `step` divides `max - min` into a whole number of steps.
+fn step_divides(min: f64, max: f64, step: f64) -> bool {
+    let steps = (max - min) / step;
+    (steps - steps.round()).abs() < EPSILON * steps.abs().max(1.0)
+}
+
+fn validate_range(criterion: &Criterion, path: &str, issues: &mut Vec<BaremeValidationIssue>) {
+    let id = Some(criterion.id.as_str());
+    let min = criterion.min.unwrap_or(0.0);
+    let Some(max) = criterion.max else {
+        issues.push(issue(format!("{}.max", path), id, "max is missing"));
+        return;
+    };
+    if !min.is_finite() || !max.is_finite() {
+        issues.push(issue(path, id, "min and max must be finite numbers"));
+        return;
+    }
+    if min > max {
+        issues.push(issue(
+            format!("{}.min", path),
+            id,
+            format!("min ({}) is greater than max ({})", min, max),
+        ));
+        return;
+    }
+    if let Some(step) = criterion.step {
+        if !step.is_finite() || step <= 0.0 {
+            issues.push(issue(
+                format!("{}.step", path),
+                id,
+                "must be greater than 0",
+            ));
+        } else if !step_divides(min, max, step) {
+            issues.push(issue(
+                format!("{}.step", path),
+                id,
+                format!("step {} does not divide the range {}–{}", step, min, max),
+            ));
+        }
+    }
+}
+
+fn validate_criterion(criterion: &Criterion, path: &str, issues: &mut Vec<BaremeValidationIssue>) {
+    let id = Some(criterion.id.as_str());
+    if criterion.name.trim().is_empty() {
+        issues.push(issue(
+            format!("{}.name", path),
+            id,
+            "criterion name is missing",
+        ));
+    }
+    if !CRITERION_TYPES.contains(&criterion.kind.as_str()) {
+        issues.push(issue(
+            format!("{}.type", path),
+            id,
+            format!("unknown criterion type \"{}\"", criterion.kind),
+        ));
+        return;
+    }
+
+    if is_scored(criterion) {
+        validate_range(criterion, path, issues);
+    }
+
+    if criterion.kind == "select" {
+        let options = criterion.options.as_deref().unwrap_or_default();
+        if options.iter().all(|option| option.trim().is_empty()) {
+            issues.push(issue(
+                format!("{}.options", path),
+                id,
+                "a select criterion needs at least one option",
+            ));
+        }
+        let mut seen = HashSet::new();
+        for (index, option) in options.iter().enumerate() {
+            if !option.trim().is_empty() && !seen.insert(option.trim().to_lowercase()) {
+                issues.push(issue(
+                    format!("{}.options[{}]", path, index),
+                    id,
+                    format!("duplicate option \"{}\"", option),
+                ));
+            }
+        }
+    }
+
+    for (index, rule) in criterion
+        .validation_rules
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+    {
+        let rule_path = format!("{}.validationRules[{}]", path, index);
+        if !RULE_TYPES.contains(&rule.kind.as_str()) {
+            issues.push(issue(
+                format!("{}.type", rule_path),
+                id,
+                format!("unknown rule type \"{}\"", rule.kind),
+            ));
+        } else if rule.kind == "required" && !rule.value.is_boolean() {
+            issues.push(issue(
+                format!("{}.value", rule_path),
+                id,
+                "must be true or false",
+            ));
+        } else if rule.kind != "required" && !rule.value.is_number() {
+            issues.push(issue(
+                format!("{}.value", rule_path),
+                id,
+                "must be a number",
+            ));
+        }
+    }
+}
+
+/// Sum of the maxima of the scored criteria, the same total the frontend
+/// falls back to when `totalPoints` is missing.
+pub fn criteria_total(bareme: &Bareme) -> f64 {
+    bareme
+        .criteria
+        .iter()
+        .filter(|criterion| is_scored(criterion) && !criterion.name.trim().is_empty())
+        .filter_map(|criterion| criterion.max)
+        .filter(|max| max.is_finite())
+        .sum()
+}
+
+pub fn validate_bareme(bareme: &Bareme) -> Vec<BaremeValidationIssue> {
+    let mut issues = Vec::new();
+
+    if bareme.id.trim().is_empty() {
+        issues.push(issue("id", None, "bareme id is missing"));
+    }
+    if bareme.name.trim().is_empty() {
+        issues.push(issue("name", None, "bareme name is missing"));
+    }
+    if bareme.criteria.is_empty() {
+        issues.push(issue(
+            "criteria",
+            None,
+            "a bareme needs at least one criterion",
+        ));
+    }
+
+    let mut criterion_ids = HashSet::new();
+    for (index, criterion) in bareme.criteria.iter().enumerate() {
+        let path = format!("criteria[{}]", index);
+        if criterion.id.trim().is_empty() {
+            issues.push(issue(
+                format!("{}.id", path),
+                None,
+                "criterion id is missing",
+            ));
+        } else if !criterion_ids.insert(criterion.id.as_str()) {
+            issues.push(issue(
+                format!("{}.id", path),
+                Some(&criterion.id),
+                format!("duplicate criterion id \"{}\"", criterion.id),
+            ));
+        }
+        validate_criterion(criterion, &path, &mut issues);
+    }
+
+    let total = criteria_total(bareme);
+    if bareme.total_points > 0.0 && (bareme.total_points - total).abs() > TOTAL_TOLERANCE {
+        issues.push(issue(
+            "totalPoints",
+            None,
+            format!(
+                "totalPoints ({}) does not match the sum of criterion maxima ({})",
+                bareme.total_points,
+                (total * 100.0).round() / 100.0
+            ),
+        ));
+    }
+
+    validate_tie_breakers(bareme, &mut issues);
+    issues
+}
+
+/// Category rules must name a category of the grid; organizer decisions list
+/// clips, which only a project has.
+fn validate_tie_breakers(bareme: &Bareme, issues: &mut Vec<BaremeValidationIssue>) {
+    let categories: HashSet<String> = bareme
+        .criteria
+        .iter()
+        .filter_map(|criterion| criterion.category.as_deref())
+        .map(fold)
+        .collect();
+    for (index, tie_breaker) in bareme.tie_breakers.iter().flatten().enumerate() {
+        let path = format!("tieBreakers[{}]", index);
+        match tie_breaker {
+            TieBreaker::CategoryScore { category } if !categories.contains(&fold(category)) => {
+                issues.push(issue(
+                    path,
+                    None,
+                    format!("tie-breaker category \"{}\" has no criteria", category),
+                ));
+            }
+            TieBreaker::OrganizerDecision { .. } => {
+                issues.push(issue(
+                    path,
+                    None,
+                    "organizer decisions belong in the project, not the barème",
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Flattens validation issues into the single error string returned by commands.
+pub fn format_issues(name: &str, issues: &[BaremeValidationIssue]) -> String {
+    let mut parts: Vec<String> = issues
+        .iter()
+        .take(MAX_REPORTED_ISSUES)
+        .map(|issue| match (&issue.criterion_id, issue.path.is_empty()) {
+            (Some(criterion), _) => format!("{} ({}): {}", issue.path, criterion, issue.message),
+            (None, true) => issue.message.clone(),
+            (None, false) => format!("{}: {}", issue.path, issue.message),
+        })
+        .collect();
+    if issues.len() > MAX_REPORTED_ISSUES {
+        parts.push(format!("and {} more", issues.len() - MAX_REPORTED_ISSUES));
+    }
+    if name.trim().is_empty() {
+        format!("Invalid bareme: {}", parts.join("; "))
+    } else {
+        format!("Invalid bareme \"{}\": {}", name.trim(), parts.join("; "))
+    }
+}
+
+/// Issue list for the `validate_bareme` command; an empty list means valid.
+pub fn bareme_issues(value: &serde_json::Value) -> Vec<BaremeValidationIssue> {
+    parse_bareme(value.clone()).err().unwrap_or_default()
+}
@@ -1,7 +1,10 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use super::bareme_revisions;
+use super::bareme_trash;
+use super::bareme_validation;
 use super::json_io;
 use super::paths;
 
@@ -37,7 +40,12 @@ pub fn default_baremes_folder() -> Result<String, String> {
     Ok(folder.to_string_lossy().to_string())
 }
 
-pub fn save_bareme_file(data: serde_json::Value, bareme_id: String) -> Result<(), String> {
+pub fn save_bareme_file(mut data: serde_json::Value, bareme_id: String) -> Result<(), String> {
+    if let Err(issues) = bareme_validation::parse_bareme(data.clone()) {
+        let name = data.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        return Err(bareme_validation::format_issues(name, &issues));
+    }
+    bareme_revisions::record_revision(&mut data, &bareme_id)?;
     let path = bareme_file_path(&data, &bareme_id)?;
     json_io::write_pretty_json(&path, &data, "save bareme")?;
     cleanup_bareme_files(Some(&path), Some(&data), &bareme_id)
@@ -46,16 +54,61 @@ pub fn save_bareme_file(data: serde_json::Value, bareme_id: String) -> Result<()
 pub fn delete_bareme_file(bareme_id: String) -> Result<(), String> {
     let legacy_path = legacy_bareme_file_path(&bareme_id)?;
     if legacy_path.exists() {
-        fs::remove_file(legacy_path).map_err(|e| format!("Failed to delete bareme: {}", e))?;
+        bareme_trash::quarantine(&legacy_path, "Deleted from the app")?;
     }
     cleanup_bareme_files(None, None, &bareme_id)
 }
 
+/// Every parseable barème in the folder, read-only (no dedup, no cleanup).
+fn folder_baremes() -> Vec<serde_json::Value> {
+    let Ok(folder) = paths::baremes_folder() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .collect()
+}
+
+/// Looks a barème up by id without touching the folder (no dedup, no cleanup).
+pub fn find_bareme(bareme_id: &str) -> Option<serde_json::Value> {
+    folder_baremes()
+        .into_iter()
+        .find(|value| value.get("id").and_then(|id| id.as_str()) == Some(bareme_id))
+}
+
+/// Stored barème that `value` would be deduplicated against on load, using the
+/// same fingerprint as the folder cleanup.
+pub fn find_duplicate(value: &serde_json::Value) -> Option<serde_json::Value> {
+    let key = bareme_dedup_key(value)?;
+    folder_baremes()
+        .into_iter()
+        .find(|candidate| bareme_dedup_key(candidate).as_deref() == Some(key.as_str()))
+}
+
+/// Id -> name of every barème in the folder, read-only like [`find_bareme`].
+pub fn bareme_names() -> HashMap<String, String> {
+    folder_baremes()
+        .into_iter()
+        .filter_map(|value| {
+            let id = value.get("id")?.as_str()?.to_string();
+            let name = value.get("name")?.as_str()?.to_string();
+            Some((id, name))
+        })
+        .collect()
+}
+
 pub fn load_baremes_files() -> Result<Vec<serde_json::Value>, String> {
     let folder = paths::baremes_folder()?;
     let mut baremes = Vec::new();
-    let mut seen = HashSet::new();
-    let mut duplicates_to_delete = Vec::new();
+    let mut seen: HashMap<String, PathBuf> = HashMap::new();
+    let mut duplicates = Vec::new();
     let mut entries: Vec<_> = fs::read_dir(&folder)
         .map_err(|e| format!("Failed to read baremes folder: {}", e))?
         .flatten()
@@ -84,22 +137,44 @@ pub fn load_baremes_files() -> Result<Vec<serde_json::Value>, String> {
             continue;
         };
 
-        if seen.insert(fingerprint) {
-            baremes.push(parsed);
-        } else {
-            duplicates_to_delete.push(path);
+        match seen.get(&fingerprint) {
+            Some(kept) if !bareme_trash::is_kept(&path) => {
+                let reason = duplicate_reason(&fingerprint, kept);
+                duplicates.push((path, reason));
+            }
+            _ => {
+                seen.entry(fingerprint).or_insert_with(|| path.clone());
+                baremes.push(parsed);
+            }
         }
     }
 
-    for duplicate in duplicates_to_delete {
-        let _ = fs::remove_file(duplicate);
+    for (duplicate, reason) in duplicates {
+        if let Err(error) = bareme_trash::quarantine(&duplicate, &reason) {
+            eprintln!("[AMV] {}", error);
+        }
     }
 
     Ok(baremes)
 }
 
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn duplicate_reason(dedup_key: &str, kept: &Path) -> String {
+    let rule = if dedup_key.starts_with("import:") {
+        "same name and criteria as an auto-imported bareme"
+    } else {
+        "same name, criteria and categories"
+    };
+    format!("Duplicate of {} ({})", file_name(kept), rule)
+}
+
 /// Single-pass cleanup of stale bareme files. Scans the folder once, parses each
-/// candidate once, and deletes any file (other than `kept_path`) that either
+/// candidate once, and quarantines any file (other than `kept_path`) that either
 /// shares `bareme_id` or matches the dedup fingerprint of `kept_value`. Replaces
 /// the previous two-scan approach (same-id + duplicate passes) that re-read every
 /// file twice on each save.
@@ -137,12 +212,24 @@ fn cleanup_bareme_files(
             .map(|value| value.trim())
             .filter(|value| !value.is_empty())
             == Some(bareme_id);
-        let dup_matches = reference
+        let dup_key = reference
             .as_deref()
-            .is_some_and(|reference| bareme_dedup_key(&parsed).as_deref() == Some(reference));
-
-        if id_matches || dup_matches {
-            let _ = fs::remove_file(path);
+            .filter(|reference| bareme_dedup_key(&parsed).as_deref() == Some(*reference));
+
+        let reason = match (kept_path, dup_key) {
+            _ if id_matches && kept_path.is_none() => "Deleted from the app".to_string(),
+            (Some(kept), _) if id_matches => {
+                format!(
+                    "Older file of bareme {}, replaced by {}",
+                    bareme_id,
+                    file_name(kept)
+                )
+            }
+            (Some(kept), Some(key)) if !bareme_trash::is_kept(&path) => duplicate_reason(key, kept),
+            _ => continue,
+        };
+        if let Err(error) = bareme_trash::quarantine(&path, &reason) {
+            eprintln!("[AMV] {}", error);
         }
     }
 
@@ -0,0 +1,417 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64_STD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::clip_links;
+use super::project_listing;
+use super::project_validation;
+use super::types::{BlindEntry, BlindIdentity, BlindMode, BlindSessionStatus, ProjectData};
+use crate::video::import::{scan_videos, VideoMetadata};
+
+/// Clip paths handed to the webview in blind mode; the backend resolves them
+/// wherever it receives a path. The code is the URL path, as the `amv-blind`
+/// protocol handler reads it.
+pub const ALIAS_PREFIX: &str = "amv-blind://localhost/";
+/// No 0/O or 1/I, so codes read back without ambiguity.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LEN: usize = 6;
+const MIN_KEY_LEN: usize = 8;
+/// Argon2id cost for deriving the sealing key from the organizer key: 19 MiB,
+/// two passes, one lane. Stored projects depend on these values.
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_PASSES: u32 = 2;
+const NONCE_LEN: usize = 12;
+/// Sub-folders explored under the clips folder when a blind project opens.
+const SESSION_SCAN_DEPTH: usize = 4;
+
+/// Entry code -> video of the blind project open in this instance. Only the
+/// open-project flow opens it, for the project file it opened.
+#[derive(Default)]
+struct BlindSession {
+    project_path: String,
+    folder: String,
+    paths: HashMap<String, PathBuf>,
+}
+
+lazy_static! {
+    static ref SESSION: Mutex<BlindSession> = Mutex::new(BlindSession::default());
+}
+
+/// What sealing hides: the clip as it was before blind mode.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedIdentity {
+    file_name: String,
+    file_path: String,
+    display_name: String,
+    author: Option<String>,
+}
+
+fn normalize_path(path: &str) -> String {
+    path.trim()
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+fn sealing_cipher(organizer_key: &str, salt: &str) -> Result<ChaCha20Poly1305, String> {
+    let params = Params::new(KDF_MEMORY_KIB, KDF_PASSES, 1, Some(32)).map_err(|e| e.to_string())?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(organizer_key.as_bytes(), salt.as_bytes(), &mut key)
+        .map_err(|e| format!("Failed to derive the organizer key: {}", e))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+/// ChaCha20-Poly1305 with a random nonce, stored in front of the ciphertext.
+/// The entry code is authenticated too, so sealed identities cannot be
+/// swapped between entries.
+fn seal(cipher: &ChaCha20Poly1305, code: &str, plain: &[u8]) -> Result<String, String> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plain,
+                aad: code.as_bytes(),
+            },
+        )
+        .map_err(|_| format!("Failed to seal entry {}", code))?;
+    Ok(BASE64_STD.encode([nonce.as_slice(), &sealed].concat()))
+}
+
+/// Fails when the key is wrong or the entry was altered; the two cannot be
+/// told apart.
+fn unseal(cipher: &ChaCha20Poly1305, entry: &BlindEntry) -> Result<Vec<u8>, String> {
+    let bytes = BASE64_STD
+        .decode(&entry.sealed)
+        .map_err(|e| format!("Corrupted entry {}: {}", entry.code, e))?;
+    if bytes.len() < NONCE_LEN {
+        return Err(format!("Corrupted entry {}", entry.code));
+    }
+    let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: sealed,
+                aad: entry.code.as_bytes(),
+            },
+        )
+        .map_err(|_| "Wrong organizer key".to_string())
+}
+
+fn new_code(taken: &HashSet<String>) -> String {
+    loop {
+        let bytes = uuid::Uuid::new_v4().into_bytes();
+        let code: String = bytes[..CODE_LEN]
+            .iter()
+            .map(|byte| CODE_ALPHABET[*byte as usize % CODE_ALPHABET.len()] as char)
+            .collect();
+        if !taken.contains(&code) {
+            return code;
+        }
+    }
+}
+
+fn parse(data: serde_json::Value) -> Result<ProjectData, String> {
+    let normalized = project_validation::normalize_project_value(data, "project")?;
+    serde_json::from_value(project_listing::project_root(&normalized).clone())
+        .map_err(|e| format!("Invalid project: {}", e))
+}
+
+fn to_value(project: &ProjectData) -> Result<serde_json::Value, String> {
+    serde_json::to_value(project).map_err(|e| e.to_string())
+}
+
+/// Whether a raw project (or a file wrapping one) is in blind mode.
+pub fn is_blind(data: &serde_json::Value) -> bool {
+    project_listing::project_root(data)
+        .get("blind")
+        .is_some_and(|blind| !blind.is_null())
+}
+
+pub fn alias_path(code: &str) -> String {
+    format!("{}{}", ALIAS_PREFIX, code)
+}
+
+/// Video behind an entry code of the open blind project.
+pub fn resolve_code(code: &str) -> Option<PathBuf> {
+    let session = SESSION.lock().ok()?;
+    session.paths.get(&code.trim().to_uppercase()).cloned()
+}
+
+/// Real path for an alias, the path itself for anything else.
+pub fn resolve_path(path: &str) -> String {
+    match path.strip_prefix(ALIAS_PREFIX) {
+        Some(code) => resolve_code(code.trim_end_matches('/'))
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string()),
+        None => path.to_string(),
+    }
+}
+
+/// Replaces the real path behind an alias in an error message, so a failed
+/// load or preview does not show the file name.
+pub fn redact_error(error: String, requested: &str) -> String {
+    if !requested.starts_with(ALIAS_PREFIX) {
+        return error;
+    }
+    let real = resolve_path(requested);
+    error
+        .replace(&real, requested)
+        .replace(&real.replace('\\', "/"), requested)
+}
+
+/// In the clips folder of an open blind project, videos are listed under
+/// their entry code and alias path; files that are not entries are left out.
+pub fn redact_scan(folder_path: &str, videos: Vec<VideoMetadata>) -> Vec<VideoMetadata> {
+    let Ok(session) = SESSION.lock() else {
+        return videos;
+    };
+    if session.folder.is_empty() || normalize_path(folder_path) != session.folder {
+        return videos;
+    }
+    let codes: HashMap<String, &String> = session
+        .paths
+        .iter()
+        .map(|(code, path)| (normalize_path(&path.to_string_lossy()), code))
+        .collect();
+    let mut redacted: Vec<VideoMetadata> = videos
+        .into_iter()
+        .filter_map(|video| {
+            let code = codes.get(&normalize_path(&video.file_path))?;
+            Some(VideoMetadata {
+                file_name: format!("{}.{}", code, video.extension),
+                file_path: alias_path(code),
+                ..video
+            })
+        })
+        .collect();
+    redacted.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    redacted
+}
+
+/// Finds each entry's video in the project's clips folder by size and
+/// content hash, and makes the codes resolvable for this instance while the
+/// project at `project_path` is open. Projects without blind mode clear the
+/// session.
+pub fn open_session(
+    project_path: &str,
+    data: &serde_json::Value,
+) -> Result<BlindSessionStatus, String> {
+    let project = parse(data.clone())?;
+    let Some(blind) = &project.blind else {
+        *SESSION.lock().map_err(|e| e.to_string())? = BlindSession {
+            project_path: normalize_path(project_path),
+            ..BlindSession::default()
+        };
+        return Ok(BlindSessionStatus::default());
+    };
+
+    let folder = project.project.clips_folder_path.trim();
+    let videos = if folder.is_empty() {
+        Vec::new()
+    } else {
+        scan_videos(folder, SESSION_SCAN_DEPTH).unwrap_or_default()
+    };
+    let sizes: HashSet<u64> = blind.entries.iter().map(|entry| entry.file_size).collect();
+    let mut by_hash: HashMap<String, PathBuf> = HashMap::new();
+    for video in videos
+        .iter()
+        .filter(|video| sizes.contains(&video.size_bytes))
+    {
+        let path = PathBuf::from(&video.file_path);
+        if let Ok(hash) = clip_links::quick_content_hash(&path) {
+            by_hash.entry(hash).or_insert(path);
+        }
+    }
+
+    let mut status = BlindSessionStatus::default();
+    let mut paths = HashMap::new();
+    for entry in &blind.entries {
+        match by_hash.get(&entry.content_hash) {
+            Some(path) => {
+                paths.insert(entry.code.clone(), path.clone());
+                status.matched.push(entry.code.clone());
+            }
+            None => status.missing.push(entry.code.clone()),
+        }
+    }
+    // Hashing can take a while; the lock is only taken to swap the session.
+    *SESSION.lock().map_err(|e| e.to_string())? = BlindSession {
+        project_path: normalize_path(project_path),
+        folder: normalize_path(folder),
+        paths,
+    };
+    Ok(status)
+}
+
+/// Forgets the codes when the project that opened the session is closed.
+pub fn close_session(project_path: &str) {
+    if let Ok(mut session) = SESSION.lock() {
+        if session.project_path == normalize_path(project_path) {
+            *session = BlindSession::default();
+        }
+    }
+}
+
+/// Gives every clip an entry code, seals its name, author and file with the
+/// organizer key and replaces them with the code, so the project can be sent
+/// to judges. Clip files must be readable to record their content hash.
+pub fn enable(data: serde_json::Value, organizer_key: &str) -> Result<serde_json::Value, String> {
+    if organizer_key.chars().count() < MIN_KEY_LEN {
+        return Err(format!(
+            "The organizer key needs at least {} characters",
+            MIN_KEY_LEN
+        ));
+    }
+    let mut project = parse(data)?;
+    if project.blind.is_some() {
+        return Err("Blind mode is already enabled".to_string());
+    }
+
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let cipher = sealing_cipher(organizer_key, &salt)?;
+    let mut taken = HashSet::new();
+    let mut entries = Vec::new();
+    let mut paths = HashMap::new();
+    for clip in &mut project.clips {
+        let path = Path::new(&clip.file_path);
+        let file_size = fs::metadata(path)
+            .map_err(|e| format!("Cannot read {}: {}", clip.file_path, e))?
+            .len();
+        let content_hash = clip_links::quick_content_hash(path)?;
+        let code = new_code(&taken);
+        taken.insert(code.clone());
+
+        let identity = SealedIdentity {
+            file_name: clip.file_name.clone(),
+            file_path: clip.file_path.clone(),
+            display_name: clip.display_name.clone(),
+            author: clip.author.clone(),
+        };
+        let plain = serde_json::to_vec(&identity).map_err(|e| e.to_string())?;
+        let sealed = seal(&cipher, &code, &plain)?;
+
+        paths.insert(code.clone(), path.to_path_buf());
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default();
+        clip.file_name = if extension.is_empty() {
+            code.clone()
+        } else {
+            format!("{}.{}", code, extension)
+        };
+        clip.file_path = alias_path(&code);
+        clip.display_name = code.clone();
+        clip.author = None;
+        entries.push(BlindEntry {
+            code,
+            clip_id: clip.id.clone(),
+            file_size,
+            content_hash,
+            sealed,
+        });
+    }
+
+    project.blind = Some(BlindMode { salt, entries });
+    // Only the open project's session learns the codes; enabling blind mode
+    // on another project file leaves it alone.
+    let mut session = SESSION.lock().map_err(|e| e.to_string())?;
+    if let Some(project_path) = project.project.file_path.as_deref() {
+        if session.project_path == normalize_path(project_path) {
+            *session = BlindSession {
+                project_path: normalize_path(project_path),
+                folder: normalize_path(&project.project.clips_folder_path),
+                paths,
+            };
+        }
+    }
+    drop(session);
+    to_value(&project)
+}
+
+fn open_identities(
+    blind: &BlindMode,
+    organizer_key: &str,
+) -> Result<Vec<(BlindEntry, SealedIdentity)>, String> {
+    let cipher = sealing_cipher(organizer_key, &blind.salt)?;
+    blind
+        .entries
+        .iter()
+        .map(|entry| {
+            let plain = unseal(&cipher, entry)?;
+            let identity = serde_json::from_slice(&plain)
+                .map_err(|e| format!("Corrupted entry {}: {}", entry.code, e))?;
+            Ok((entry.clone(), identity))
+        })
+        .collect()
+}
+
+/// Entry code -> clip identity, for the organizer at results time.
+pub fn reveal(data: serde_json::Value, organizer_key: &str) -> Result<Vec<BlindIdentity>, String> {
+    let project = parse(data)?;
+    let blind = project
+        .blind
+        .as_ref()
+        .ok_or_else(|| "Blind mode is not enabled".to_string())?;
+    Ok(open_identities(blind, organizer_key)?
+        .into_iter()
+        .map(|(entry, identity)| BlindIdentity {
+            code: entry.code,
+            clip_id: entry.clip_id,
+            file_name: identity.file_name,
+            file_path: identity.file_path,
+            display_name: identity.display_name,
+            author: identity.author,
+        })
+        .collect())
+}
+
+/// Puts names and files back on the clips and leaves blind mode. Clips whose
+/// video was found in this session keep that path, in case it moved.
+pub fn disable(data: serde_json::Value, organizer_key: &str) -> Result<serde_json::Value, String> {
+    let mut project = parse(data)?;
+    let blind = project
+        .blind
+        .take()
+        .ok_or_else(|| "Blind mode is not enabled".to_string())?;
+    let identities = open_identities(&blind, organizer_key)?;
+    // The project stays open without blind mode: its session keeps the
+    // project but no longer resolves codes.
+    let session_paths = match (SESSION.lock(), project.project.file_path.as_deref()) {
+        (Ok(mut session), Some(project_path))
+            if session.project_path == normalize_path(project_path) =>
+        {
+            session.folder.clear();
+            std::mem::take(&mut session.paths)
+        }
+        _ => HashMap::new(),
+    };
+    let by_clip: HashMap<&str, &(BlindEntry, SealedIdentity)> = identities
+        .iter()
+        .map(|pair| (pair.0.clip_id.as_str(), pair))
+        .collect();
+    for clip in &mut project.clips {
+        let Some((entry, identity)) = by_clip.get(clip.id.as_str()) else {
+            continue;
+        };
+        clip.file_name = identity.file_name.clone();
+        clip.file_path = session_paths
+            .get(&entry.code)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| identity.file_path.clone());
+        clip.display_name = identity.display_name.clone();
+        clip.author = identity.author.clone();
+    }
+    to_value(&project)
+}
@@ -0,0 +1,553 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+
+use super::baremes;
+use super::blind;
+use super::json_io;
+use super::project_validation;
+use super::types::ImportedProjectBundle;
+
+const BUNDLE_FORMAT: &str = "amvproj";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_ENTRY: &str = "project.json";
+const RESULT_NOTES_ENTRY: &str = "result-notes.json";
+const BAREMES_DIR: &str = "baremes/";
+const JUDGES_DIR: &str = "judges/";
+const SCREENSHOTS_DIR: &str = "screenshots/";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    format: String,
+    format_version: u32,
+    created_at: String,
+    app_version: String,
+    project_name: String,
+    judge_name: String,
+    files: Vec<BundleEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleEntry {
+    path: String,
+    sha256: String,
+    size: u64,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn entry_file_name(value: &str) -> String {
+    let name: String = value
+        .trim()
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == '.' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "item".to_string()
+    } else {
+        name
+    }
+}
+
+fn pretty_bytes(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+/// Every barème id the project refers to, from the project itself and from
+/// each note (notes keep the id of the grid they were scored with).
+fn referenced_bareme_ids(project: &serde_json::Value) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    let mut push = |value: &serde_json::Value| {
+        if let Some(id) = value.as_str().map(str::trim).filter(|id| !id.is_empty()) {
+            ids.insert(id.to_string());
+        }
+    };
+    push(&project["baremeId"]);
+    push(&project["project"]["baremeId"]);
+    if let Some(notes) = project["notes"].as_object() {
+        notes.values().for_each(|note| push(&note["baremeId"]));
+    }
+    ids
+}
+
+/// Resolves barèmes from the copies embedded in the project first, then from
+/// the local barèmes folder.
+fn collect_baremes(project: &serde_json::Value) -> Vec<serde_json::Value> {
+    let mut embedded: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+    let candidates = std::iter::once(&project["bareme"])
+        .chain(project["baremes"].as_array().into_iter().flatten());
+    for candidate in candidates {
+        if let Some(id) = candidate["id"].as_str() {
+            embedded
+                .entry(id.to_string())
+                .or_insert_with(|| candidate.clone());
+        }
+    }
+
+    referenced_bareme_ids(project)
+        .into_iter()
+        .filter_map(|id| embedded.remove(&id).or_else(|| baremes::find_bareme(&id)))
+        .collect()
+}
+
+struct BundleWriter {
+    zip: zip::ZipWriter<Cursor<Vec<u8>>>,
+    entries: Vec<BundleEntry>,
+}
+
+impl BundleWriter {
+    fn new() -> Self {
+        Self {
+            zip: zip::ZipWriter::new(Cursor::new(Vec::new())),
+            entries: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, path: &str, bytes: &[u8]) -> Result<(), String> {
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        self.zip
+            .start_file(path, options)
+            .and_then(|_| self.zip.write_all(bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to write bundle entry {}: {}", path, e))?;
+        self.entries.push(BundleEntry {
+            path: path.to_string(),
+            sha256: sha256_hex(bytes),
+            size: bytes.len() as u64,
+        });
+        Ok(())
+    }
+
+    fn finish(mut self, mut manifest: BundleManifest) -> Result<Vec<u8>, String> {
+        manifest.files = std::mem::take(&mut self.entries);
+        let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+        let options = SimpleFileOptions::default();
+        self.zip
+            .start_file(MANIFEST_ENTRY, options)
+            .and_then(|_| self.zip.write_all(&manifest).map_err(Into::into))
+            .map_err(|e| format!("Failed to write bundle manifest: {}", e))?;
+        let cursor = self
+            .zip
+            .finish()
+            .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// Same as `safeFilePart` in `src/utils/screenshot.ts`, with accented Latin
+/// letters folded the way its NFKD pass leaves them.
+fn screenshot_file_part(value: &str) -> String {
+    let folded: String = value
+        .chars()
+        .map(|ch| match ch {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'È' | 'É' | 'Ê' | 'Ë' => 'E',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'O',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+            'ç' => 'c',
+            'Ç' => 'C',
+            'ñ' => 'n',
+            'Ñ' => 'N',
+            'ý' | 'ÿ' => 'y',
+            other => other,
+        })
+        .collect();
+    let mut part = String::new();
+    for ch in folded.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '-') {
+            part.push(ch);
+        } else if !part.ends_with('-') {
+            part.push('-');
+        }
+    }
+    let part: String = part.trim_matches('-').chars().take(60).collect();
+    if part.is_empty() {
+        "capture".to_string()
+    } else {
+        part
+    }
+}
+
+/// Captures of favorite clips, found by the name the player gives them
+/// (`<page>-<clip>-<timestamp>.png`) in the project's captures folder, where
+/// an imported bundle puts them, and in the clips folder.
+fn favorite_screenshots(project: &serde_json::Value) -> Vec<PathBuf> {
+    let labels: Vec<String> = project["clips"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|clip| clip["favorite"].as_bool() == Some(true))
+        .filter_map(|clip| clip["displayName"].as_str())
+        .map(|name| format!("-{}-", screenshot_file_part(name).to_lowercase()))
+        .collect();
+    if labels.is_empty() {
+        return Vec::new();
+    }
+
+    let mut folders = Vec::new();
+    if let Some(file_path) = project["project"]["filePath"]
+        .as_str()
+        .filter(|path| !path.is_empty())
+    {
+        folders.push(screenshots_folder(Path::new(file_path)));
+    }
+    if let Some(clips_folder) = project["project"]["clipsFolderPath"]
+        .as_str()
+        .filter(|path| !path.is_empty())
+    {
+        folders.push(PathBuf::from(clips_folder));
+    }
+
+    let mut found = Vec::new();
+    for folder in folders {
+        let Ok(entries) = fs::read_dir(&folder) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let is_image = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    matches!(
+                        extension.to_lowercase().as_str(),
+                        "png" | "jpg" | "jpeg" | "webp"
+                    )
+                });
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if path.is_file() && is_image && labels.iter().any(|label| name.contains(label)) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Bundles `screenshot_paths` along with the favorite clips' captures found
+/// next to the project.
+pub fn export_bundle(
+    data: serde_json::Value,
+    bundle_path: String,
+    screenshot_paths: Vec<String>,
+) -> Result<(), String> {
+    let data = project_validation::normalize_project_value(data, "project")?;
+    let project = super::project_listing::project_root(&data).clone();
+
+    let mut writer = BundleWriter::new();
+    writer.add(PROJECT_ENTRY, &pretty_bytes(&project)?)?;
+
+    for bareme in collect_baremes(&project) {
+        let id = bareme["id"].as_str().unwrap_or("bareme");
+        writer.add(
+            &format!("{}{}.json", BAREMES_DIR, entry_file_name(id)),
+            &pretty_bytes(&bareme)?,
+        )?;
+    }
+
+    if let Some(judges) = project["importedJudges"].as_array() {
+        for (index, judge) in judges.iter().enumerate() {
+            let name = judge["judgeName"].as_str().unwrap_or("judge");
+            writer.add(
+                &format!(
+                    "{}{:02}-{}.json",
+                    JUDGES_DIR,
+                    index + 1,
+                    entry_file_name(name)
+                ),
+                &pretty_bytes(judge)?,
+            )?;
+        }
+    }
+
+    let result_notes = &project["project"]["resultNotes"];
+    if result_notes
+        .as_object()
+        .is_some_and(|notes| !notes.is_empty())
+    {
+        writer.add(RESULT_NOTES_ENTRY, &pretty_bytes(result_notes)?)?;
+    }
+
+    let mut used_names = BTreeSet::new();
+    let mut seen_paths = BTreeSet::new();
+    let screenshots = screenshot_paths
+        .into_iter()
+        .map(PathBuf::from)
+        .chain(favorite_screenshots(&project));
+    for path in screenshots {
+        if !seen_paths.insert(fs::canonicalize(&path).unwrap_or_else(|_| path.clone())) {
+            continue;
+        }
+        let bytes = fs::read(&path)
+            .map_err(|e| format!("Failed to read screenshot {}: {}", path.display(), e))?;
+        let base = entry_file_name(&path.file_name().unwrap_or_default().to_string_lossy());
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !used_names.insert(name.clone()) {
+            name = format!("{}-{}", suffix, base);
+            suffix += 1;
+        }
+        writer.add(&format!("{}{}", SCREENSHOTS_DIR, name), &bytes)?;
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        format_version: BUNDLE_FORMAT_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        project_name: project["project"]["name"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        judge_name: project["project"]["judgeName"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        files: Vec::new(),
+    };
+    let bytes = writer.finish(manifest)?;
+    json_io::write_atomic(Path::new(&bundle_path), &bytes, "export bundle")
+}
+
+/// Reads every entry listed in the manifest and checks its size and checksum.
+fn read_bundle(bundle_path: &Path) -> Result<(BundleManifest, BTreeMap<String, Vec<u8>>), String> {
+    let file = fs::File::open(bundle_path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read bundle: {}", e))?;
+
+    let read_entry = |archive: &mut zip::ZipArchive<fs::File>, name: &str| {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| format!("Bundle entry {} is missing: {}", name, e))?;
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read bundle entry {}: {}", name, e))?;
+        Ok::<_, String>(bytes)
+    };
+
+    let manifest: BundleManifest =
+        serde_json::from_slice(&read_entry(&mut archive, MANIFEST_ENTRY)?)
+            .map_err(|e| format!("Failed to parse bundle manifest: {}", e))?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(format!("Not an .amvproj bundle ({})", manifest.format));
+    }
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format {} is newer than the supported format {}",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+
+    let mut files = BTreeMap::new();
+    for listed in &manifest.files {
+        let bytes = read_entry(&mut archive, &listed.path)?;
+        if bytes.len() as u64 != listed.size || sha256_hex(&bytes) != listed.sha256 {
+            return Err(format!(
+                "Bundle entry {} is corrupted (checksum mismatch)",
+                listed.path
+            ));
+        }
+        files.insert(listed.path.clone(), bytes);
+    }
+    if !files.contains_key(PROJECT_ENTRY) {
+        return Err("Bundle does not contain a project".to_string());
+    }
+
+    Ok((manifest, files))
+}
+
+fn parse_entry(files: &BTreeMap<String, Vec<u8>>, name: &str) -> Result<serde_json::Value, String> {
+    let bytes = files
+        .get(name)
+        .ok_or_else(|| format!("Bundle entry {} is missing", name))?;
+    serde_json::from_slice(bytes)
+        .map_err(|e| format!("Failed to parse bundle entry {}: {}", name, e))
+}
+
+/// Points each clip at the file of the same name in `clips_folder`. Returns the
+/// file names that could not be found. Blind clips keep their alias paths; the
+/// blind session finds them in the new clips folder when the project opens.
+fn relink_clips(
+    project: &mut serde_json::Value,
+    clips_folder: &str,
+) -> Result<(usize, Vec<String>), String> {
+    project["project"]["clipsFolderPath"] = serde_json::Value::String(clips_folder.to_string());
+    if blind::is_blind(project) {
+        return Ok((0, Vec::new()));
+    }
+    let videos = crate::video::import::scan_videos(clips_folder, 1)?;
+    let by_name: BTreeMap<String, String> = videos
+        .into_iter()
+        .map(|video| (video.file_name.to_lowercase(), video.file_path))
+        .collect();
+
+    let mut relinked = 0;
+    let mut missing = Vec::new();
+    if let Some(clips) = project["clips"].as_array_mut() {
+        for clip in clips {
+            let file_name = clip["fileName"].as_str().unwrap_or("").to_string();
+            match by_name.get(&file_name.to_lowercase()) {
+                Some(path) => {
+                    clip["filePath"] = serde_json::Value::String(path.clone());
+                    relinked += 1;
+                }
+                None => missing.push(file_name),
+            }
+        }
+    }
+    Ok((relinked, missing))
+}
+
+/// Adds bundled judges and result notes that the bundled project does not
+/// already carry (for instance when they were edited as separate entries).
+fn merge_side_entries(
+    project: &mut serde_json::Value,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<(), String> {
+    for name in files.keys().filter(|name| name.starts_with(JUDGES_DIR)) {
+        let judge = parse_entry(files, name)?;
+        let Some(judge_name) = judge["judgeName"].as_str().map(str::to_lowercase) else {
+            continue;
+        };
+        if !project["importedJudges"].is_array() {
+            project["importedJudges"] = serde_json::Value::Array(Vec::new());
+        }
+        if let Some(judges) = project["importedJudges"].as_array_mut() {
+            let known = judges.iter().any(|existing| {
+                existing["judgeName"]
+                    .as_str()
+                    .map(str::to_lowercase)
+                    .as_deref()
+                    == Some(judge_name.as_str())
+            });
+            if !known {
+                judges.push(judge);
+            }
+        }
+    }
+
+    if files.contains_key(RESULT_NOTES_ENTRY) {
+        if let serde_json::Value::Object(bundled) = parse_entry(files, RESULT_NOTES_ENTRY)? {
+            if !project["project"]["resultNotes"].is_object() {
+                project["project"]["resultNotes"] = serde_json::json!({});
+            }
+            if let Some(notes) = project["project"]["resultNotes"].as_object_mut() {
+                for (clip_id, note) in bundled {
+                    notes.entry(clip_id).or_insert(note);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn screenshots_folder(project_path: &Path) -> PathBuf {
+    let stem = project_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "projet".to_string());
+    project_path.with_file_name(format!("{} - captures", stem))
+}
+
+/// Refuses to replace an existing project unless `overwrite` is set.
+pub fn import_bundle(
+    bundle_path: String,
+    project_path: String,
+    clips_folder: Option<String>,
+    overwrite: bool,
+) -> Result<ImportedProjectBundle, String> {
+    if !overwrite && Path::new(&project_path).exists() {
+        return Err(format!("A project already exists at {}", project_path));
+    }
+    let (_manifest, files) = read_bundle(Path::new(&bundle_path))?;
+    // Validate first so that every edit below works on a well-formed project.
+    let mut project = project_validation::normalize_project_value(
+        parse_entry(&files, PROJECT_ENTRY)?,
+        "bundled project",
+    )?;
+    merge_side_entries(&mut project, &files)?;
+
+    let bundled_baremes = files
+        .keys()
+        .filter(|name| name.starts_with(BAREMES_DIR))
+        .map(|name| parse_entry(&files, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !bundled_baremes.is_empty() {
+        let main_id = project["baremeId"].as_str().unwrap_or("").to_string();
+        if let Some(main) = bundled_baremes
+            .iter()
+            .find(|bareme| bareme["id"].as_str() == Some(main_id.as_str()))
+        {
+            project["bareme"] = main.clone();
+        }
+        project["baremes"] = serde_json::Value::Array(bundled_baremes);
+    }
+
+    let (relinked_clips, missing_clips) = match clips_folder.as_deref().map(str::trim) {
+        Some(folder) if !folder.is_empty() => relink_clips(&mut project, folder)?,
+        _ => (0, Vec::new()),
+    };
+
+    let target = Path::new(&project_path);
+    project["project"]["filePath"] = serde_json::Value::String(project_path.clone());
+    let project = project_validation::normalize_project_value(project, "bundled project")?;
+
+    let mut screenshots = Vec::new();
+    let screenshot_entries: Vec<_> = files
+        .iter()
+        .filter(|(name, _)| name.starts_with(SCREENSHOTS_DIR))
+        .collect();
+    if !screenshot_entries.is_empty() {
+        let folder = screenshots_folder(target);
+        fs::create_dir_all(&folder)
+            .map_err(|e| format!("Failed to create captures folder: {}", e))?;
+        for (name, bytes) in screenshot_entries {
+            let file_name = entry_file_name(&name[SCREENSHOTS_DIR.len()..]);
+            let path = folder.join(file_name);
+            json_io::write_atomic(&path, bytes, "extract screenshot")?;
+            screenshots.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    super::project_files::save_project_file(
+        project.clone(),
+        project_path.clone(),
+        Some("Imported project bundle"),
+    )?;
+
+    Ok(ImportedProjectBundle {
+        project,
+        file_path: project_path,
+        relinked_clips,
+        missing_clips,
+        screenshots,
+    })
+}
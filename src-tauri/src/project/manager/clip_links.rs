@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use super::blind;
+use super::project_listing;
+use super::project_validation;
+use super::types::{ClipRelinkCandidate, ClipRelinkProposal, MissingClip};
+use crate::video::import::{scan_videos, VideoMetadata};
+
+/// Sub-folders explored under the folder picked by the user.
+const RELINK_SCAN_DEPTH: usize = 4;
+/// Bytes hashed at each end of a video for the quick content hash.
+const HASH_SAMPLE_BYTES: u64 = 1024 * 1024;
+/// Keys recorded on a clip once its file is seen, used to confirm later relinks.
+const CLIP_SIZE_KEY: &str = "fileSize";
+const CLIP_HASH_KEY: &str = "contentHash";
+/// Modification time (Unix seconds) the fingerprint was taken at.
+const CLIP_MODIFIED_KEY: &str = "fileModifiedAt";
+
+/// Hash of the file size plus its first and last megabyte. Reading whole
+/// videos would take minutes on a contest folder; this is enough to tell two
+/// encodes of the same clip apart.
+pub fn quick_content_hash(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+    let mut buffer = vec![0u8; HASH_SAMPLE_BYTES.min(size) as usize];
+    file.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+    hasher.update(&buffer);
+    if size > HASH_SAMPLE_BYTES {
+        let tail = HASH_SAMPLE_BYTES.min(size - HASH_SAMPLE_BYTES);
+        file.seek(SeekFrom::End(-(tail as i64)))
+            .map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u8; tail as usize];
+        file.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+        hasher.update(&buffer);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|age| age.as_secs())
+}
+
+fn set_fingerprint(clip: &mut serde_json::Value, metadata: &fs::Metadata, hash: String) {
+    clip[CLIP_SIZE_KEY] = serde_json::json!(metadata.len());
+    clip[CLIP_HASH_KEY] = serde_json::Value::String(hash);
+    clip[CLIP_MODIFIED_KEY] = match modified_secs(metadata) {
+        Some(secs) => serde_json::json!(secs),
+        None => serde_json::Value::Null,
+    };
+}
+
+/// Records size and quick hash on every clip whose file is present, so that a
+/// relink after the files move can match on them and not on the name alone.
+/// Done on save; the hash is only taken again when the file's size or
+/// modification time changed.
+pub fn record_fingerprints(data: &mut serde_json::Value) {
+    let project = match data.get_mut("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => data,
+    };
+    let Some(clips) = project["clips"].as_array_mut() else {
+        return;
+    };
+    for clip in clips {
+        let path = clip["filePath"].as_str().unwrap_or("").trim().to_string();
+        let path = Path::new(&path);
+        let Ok(metadata) = fs::metadata(path) else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let unchanged = clip[CLIP_HASH_KEY].is_string()
+            && clip[CLIP_SIZE_KEY].as_u64() == Some(metadata.len())
+            && clip[CLIP_MODIFIED_KEY].as_u64() == modified_secs(&metadata);
+        if unchanged {
+            continue;
+        }
+        let Ok(hash) = quick_content_hash(path) else {
+            continue;
+        };
+        set_fingerprint(clip, &metadata, hash);
+    }
+}
+
+/// Blind clips count as present when the open blind session found them.
+fn clip_is_missing(clip: &serde_json::Value) -> bool {
+    let path = clip["filePath"].as_str().unwrap_or("").trim();
+    path.is_empty() || !Path::new(&blind::resolve_path(path)).is_file()
+}
+
+pub fn find_missing_clips(data: &serde_json::Value) -> Vec<MissingClip> {
+    let Some(clips) = project_listing::project_root(data)["clips"].as_array() else {
+        return Vec::new();
+    };
+    clips
+        .iter()
+        .filter(|clip| clip_is_missing(clip))
+        .map(|clip| MissingClip {
+            clip_id: clip["id"].as_str().unwrap_or("").to_string(),
+            file_name: clip["fileName"].as_str().unwrap_or("").to_string(),
+            file_path: clip["filePath"].as_str().unwrap_or("").to_string(),
+            display_name: clip["displayName"].as_str().unwrap_or("").to_string(),
+        })
+        .collect()
+}
+
+fn file_stem_key(name: &str) -> String {
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    stem.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Scores one candidate for a missing clip by its name, then by the size and
+/// hash recorded while the file was present. A file with the recorded size
+/// and content hash is the clip under any name.
+fn score_candidate(
+    clip: &serde_json::Value,
+    video: &VideoMetadata,
+    use_content_hash: bool,
+    hashes: &mut HashMap<String, Option<String>>,
+) -> Option<ClipRelinkCandidate> {
+    let clip_name = clip["fileName"].as_str().unwrap_or("");
+    let mut reasons = Vec::new();
+    let mut score: u8 = if video.file_name.eq_ignore_ascii_case(clip_name) {
+        reasons.push("name");
+        60
+    } else if !clip_name.is_empty() && file_stem_key(&video.file_name) == file_stem_key(clip_name) {
+        reasons.push("similar name");
+        35
+    } else {
+        0
+    };
+
+    let recorded_size = clip[CLIP_SIZE_KEY].as_u64();
+    let same_size = recorded_size == Some(video.size_bytes);
+    if same_size {
+        reasons.push("size");
+        score += 25;
+    } else if recorded_size.is_some() {
+        score = score.saturating_sub(20);
+    }
+
+    // The quick hash covers the size, so only files of the recorded size (or
+    // name matches of clips without one) are worth hashing.
+    if use_content_hash && (same_size || (score > 0 && recorded_size.is_none())) {
+        if let Some(expected) = clip[CLIP_HASH_KEY].as_str() {
+            let actual = hashes
+                .entry(video.file_path.clone())
+                .or_insert_with(|| quick_content_hash(Path::new(&video.file_path)).ok());
+            if actual.as_deref() == Some(expected) {
+                reasons.push("content hash");
+                score = 100;
+            } else {
+                score = score.saturating_sub(30);
+            }
+        }
+    }
+
+    if score == 0 {
+        return None;
+    }
+    Some(ClipRelinkCandidate {
+        file_name: video.file_name.clone(),
+        file_path: video.file_path.clone(),
+        size_bytes: video.size_bytes,
+        score: score.min(100),
+        reasons: reasons.into_iter().map(str::to_string).collect(),
+    })
+}
+
+pub fn propose_relinks(
+    data: &serde_json::Value,
+    folder_path: &str,
+    use_content_hash: bool,
+) -> Result<Vec<ClipRelinkProposal>, String> {
+    // Candidates would show judges the real file names; the blind session
+    // finds blind clips by content hash instead.
+    if blind::is_blind(data) {
+        return Ok(Vec::new());
+    }
+    let videos = scan_videos(folder_path, RELINK_SCAN_DEPTH)?;
+    let mut hashes = HashMap::new();
+    let Some(clips) = project_listing::project_root(data)["clips"].as_array() else {
+        return Ok(Vec::new());
+    };
+
+    Ok(clips
+        .iter()
+        .filter(|clip| clip_is_missing(clip))
+        .map(|clip| {
+            let mut candidates: Vec<_> = videos
+                .iter()
+                .filter_map(|video| score_candidate(clip, video, use_content_hash, &mut hashes))
+                .collect();
+            candidates.sort_by(|a, b| b.score.cmp(&a.score).then(a.file_path.cmp(&b.file_path)));
+            ClipRelinkProposal {
+                clip_id: clip["id"].as_str().unwrap_or("").to_string(),
+                file_name: clip["fileName"].as_str().unwrap_or("").to_string(),
+                candidates,
+            }
+        })
+        .collect())
+}
+
+/// Rewrites `filePath` for every clip in `relinks` (clip id -> new path) and
+/// records size and hash so the next relink can confirm its match. When no
+/// clips folder is given, the folder holding most relinked files is used.
+pub fn apply_relinks(
+    data: serde_json::Value,
+    relinks: HashMap<String, String>,
+    clips_folder_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    if blind::is_blind(&data) {
+        return Err("Clips of a blind project cannot be relinked".to_string());
+    }
+    let mut data = project_validation::normalize_project_value(data, "project")?;
+    let project = match data.get_mut("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => &mut data,
+    };
+
+    let mut folder_votes: HashMap<String, usize> = HashMap::new();
+    if let Some(clips) = project["clips"].as_array_mut() {
+        for clip in clips {
+            let Some(new_path) = clip["id"].as_str().and_then(|id| relinks.get(id)) else {
+                continue;
+            };
+            let path = Path::new(new_path);
+            let metadata =
+                fs::metadata(path).map_err(|e| format!("Cannot relink to {}: {}", new_path, e))?;
+            clip["filePath"] = serde_json::Value::String(new_path.clone());
+            match quick_content_hash(path) {
+                Ok(hash) => set_fingerprint(clip, &metadata, hash),
+                Err(_) => clip[CLIP_SIZE_KEY] = serde_json::json!(metadata.len()),
+            }
+            if let Some(parent) = path.parent() {
+                *folder_votes
+                    .entry(parent.to_string_lossy().to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let folder = clips_folder_path
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .or_else(|| {
+            folder_votes
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                .map(|(folder, _)| folder)
+        });
+    if let Some(folder) = folder {
+        project["project"]["clipsFolderPath"] = serde_json::Value::String(folder);
+    }
+
+    Ok(data)
+}
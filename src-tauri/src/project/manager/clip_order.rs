@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use sha2::{Digest, Sha256};
+
+use super::project_listing;
+use super::project_validation;
+use super::types::{
+    ClipInfo, ClipOrderMethod, ClipOrderPlan, ClipOrderSettings, JudgeClipOrder, ProjectData,
+};
+
+fn judge_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn parse(data: serde_json::Value) -> Result<ProjectData, String> {
+    let normalized = project_validation::normalize_project_value(data, "project")?;
+    serde_json::from_value(project_listing::project_root(&normalized).clone())
+        .map_err(|e| format!("Invalid project: {}", e))
+}
+
+/// SplitMix64 seeded from a hash of the settings seed, so an order only
+/// depends on the seed text and never on the platform.
+struct SeededRng(u64);
+
+impl SeededRng {
+    fn new(parts: &[&str]) -> Self {
+        let digest = Sha256::digest(parts.join("|").as_bytes());
+        let mut state = [0u8; 8];
+        state.copy_from_slice(&digest[..8]);
+        SeededRng(u64::from_le_bytes(state))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher-Yates.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = (self.next() % (index as u64 + 1)) as usize;
+            items.swap(index, other);
+        }
+    }
+}
+
+/// Row `row` of a Williams design for `n` items: 0, 1, n-1, 2, n-2... shifted
+/// by the row. Odd sizes need twice as many rows, the second half reversed,
+/// to balance which item follows which.
+fn williams_row(n: usize, row: usize) -> Vec<usize> {
+    let rows = if n % 2 == 0 { n } else { 2 * n };
+    let row = row % rows.max(1);
+    let shift = row % n.max(1);
+    let mut sequence: Vec<usize> = (0..n)
+        .map(|j| {
+            if j % 2 == 1 {
+                j.div_ceil(2)
+            } else {
+                (n - j / 2) % n
+            }
+        })
+        .map(|item| (item + shift) % n)
+        .collect();
+    if row >= n {
+        sequence.reverse();
+    }
+    sequence
+}
+
+/// Clips in a fixed base order, by id, split into the blocks that are ordered
+/// on their own: one per contest category when grouped, in the order of the
+/// category presets, then other categories by name, uncategorized last.
+fn blocks(project: &ProjectData, group_by_category: bool) -> Vec<(String, Vec<&ClipInfo>)> {
+    let mut clips: Vec<&ClipInfo> = project.clips.iter().collect();
+    clips.sort_by(|a, b| a.id.cmp(&b.id));
+    if !group_by_category {
+        return vec![(String::new(), clips)];
+    }
+
+    let presets = &project.project.settings.contest_category_presets;
+    let mut grouped: BTreeMap<(usize, String), Vec<&ClipInfo>> = BTreeMap::new();
+    for clip in clips {
+        let category = clip
+            .contest_category
+            .as_deref()
+            .map(str::trim)
+            .filter(|category| !category.is_empty());
+        let rank = match category {
+            Some(category) => presets
+                .iter()
+                .position(|preset| preset.trim().eq_ignore_ascii_case(category))
+                .unwrap_or(presets.len()),
+            None => presets.len() + 1,
+        };
+        let name = category.map(str::to_lowercase).unwrap_or_default();
+        grouped.entry((rank, name)).or_default().push(clip);
+    }
+    grouped
+        .into_iter()
+        .map(|((_, name), clips)| (name, clips))
+        .collect()
+}
+
+/// The panel the orders are drawn for: the settings' judges, or the project's
+/// own and imported judges sorted by name, so that every judge's file
+/// resolves the same rows.
+fn panel(project: &ProjectData, settings: &ClipOrderSettings) -> Vec<String> {
+    let names: Vec<String> = if settings.judges.is_empty() {
+        let mut names: Vec<String> = std::iter::once(project.project.judge_name.clone())
+            .chain(
+                project
+                    .imported_judges
+                    .iter()
+                    .map(|judge| judge.judge_name.clone()),
+            )
+            .collect();
+        names.sort_by_key(|name| judge_key(name));
+        names
+    } else {
+        settings.judges.clone()
+    };
+    let mut seen = HashSet::new();
+    names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && seen.insert(judge_key(name)))
+        .collect()
+}
+
+fn judge_order(
+    project: &ProjectData,
+    settings: &ClipOrderSettings,
+    judge_name: &str,
+    row: usize,
+) -> Vec<String> {
+    let key = judge_key(judge_name);
+    let mut judge_rng = SeededRng::new(&["shuffle", &settings.seed, &key]);
+    let mut order = Vec::with_capacity(project.clips.len());
+    for (category, mut clips) in blocks(project, settings.group_by_category) {
+        match settings.method {
+            ClipOrderMethod::Shuffle => {
+                judge_rng.shuffle(&mut clips);
+                order.extend(clips.iter().map(|clip| clip.id.clone()));
+            }
+            ClipOrderMethod::LatinSquare => {
+                // Every judge shares the base order; only the row differs.
+                SeededRng::new(&["latin", &settings.seed, &category]).shuffle(&mut clips);
+                order.extend(
+                    williams_row(clips.len(), row)
+                        .into_iter()
+                        .map(|index| clips[index].id.clone()),
+                );
+            }
+        }
+    }
+    order
+}
+
+fn with_seed(settings: ClipOrderSettings) -> ClipOrderSettings {
+    if !settings.seed.trim().is_empty() {
+        return settings;
+    }
+    let seed = uuid::Uuid::new_v4().simple().to_string();
+    ClipOrderSettings {
+        seed: seed[..12].to_string(),
+        ..settings
+    }
+}
+
+/// The settings to draw with, seed drawn and panel resolved, so that the
+/// settings returned or stored in the project draw the same orders in any
+/// judge's file.
+fn settings_or_stored(
+    project: &ProjectData,
+    settings: Option<ClipOrderSettings>,
+) -> ClipOrderSettings {
+    let settings = with_seed(
+        settings
+            .or_else(|| project.clip_order.clone())
+            .unwrap_or_default(),
+    );
+    ClipOrderSettings {
+        judges: panel(project, &settings),
+        ..settings
+    }
+}
+
+/// Each judge's clip order for the settings, or for the ones stored in the
+/// project; a missing seed or panel is drawn and returned in the plan.
+pub fn plan(
+    data: serde_json::Value,
+    settings: Option<ClipOrderSettings>,
+) -> Result<ClipOrderPlan, String> {
+    let project = parse(data)?;
+    let settings = settings_or_stored(&project, settings);
+    let judges = &settings.judges;
+    if judges.is_empty() {
+        return Err("No judge to draw a clip order for".to_string());
+    }
+    let orders = judges
+        .iter()
+        .enumerate()
+        .map(|(row, judge_name)| JudgeClipOrder {
+            judge_name: judge_name.clone(),
+            clip_ids: judge_order(&project, &settings, judge_name, row),
+        })
+        .collect();
+    Ok(ClipOrderPlan { settings, orders })
+}
+
+/// Sorts the project's clips in `judge_name`'s order (the project's judge by
+/// default) and stores the settings so the order can be drawn again.
+pub fn apply(
+    data: serde_json::Value,
+    settings: Option<ClipOrderSettings>,
+    judge_name: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let mut project = parse(data)?;
+    let settings = settings_or_stored(&project, settings);
+    let judge_name = judge_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| project.project.judge_name.clone());
+    if judge_name.trim().is_empty() {
+        return Err("The project has no judge name to draw a clip order for".to_string());
+    }
+
+    let row = settings
+        .judges
+        .iter()
+        .position(|name| judge_key(name) == judge_key(&judge_name));
+    let row = match (settings.method, row) {
+        (_, Some(row)) => row,
+        (ClipOrderMethod::Shuffle, None) => 0,
+        (ClipOrderMethod::LatinSquare, None) => {
+            return Err(format!("{} is not in the judging panel", judge_name.trim()));
+        }
+    };
+
+    let order = judge_order(&project, &settings, &judge_name, row);
+    let positions: HashMap<&str, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(position, id)| (id.as_str(), position))
+        .collect();
+    project
+        .clips
+        .sort_by_key(|clip| positions.get(clip.id.as_str()).copied());
+    for (position, clip) in project.clips.iter_mut().enumerate() {
+        clip.order = position as u32;
+    }
+    project.clip_order = Some(settings);
+    serde_json::to_value(project).map_err(|e| e.to_string())
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::json_io;
+use super::results::ResultsModel;
+use super::results_workbook::{self, CellValue, Labels, ResultSheet};
+
+/// Same layout as `createCsvDocument` in `csvWorkbook.ts`: semicolons, CRLF
+/// and a BOM so Excel reads accented text as UTF-8.
+const SEPARATOR: &str = ";";
+const LINE_BREAK: &str = "\r\n";
+const BOM: &str = "\u{feff}";
+
+fn escape_cell(raw: &str) -> String {
+    if raw.contains(['"', ';', '\r', '\n']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw.to_string()
+    }
+}
+
+/// Formulas are written as the value they show, since CSV has no formulas.
+fn cell_text(value: &CellValue) -> String {
+    match value {
+        CellValue::Text(text) => text.clone(),
+        CellValue::Number(number)
+        | CellValue::Formula {
+            cached: Some(number),
+            ..
+        } => number.to_string(),
+        CellValue::Blank | CellValue::Formula { cached: None, .. } => String::new(),
+    }
+}
+
+fn sheet_lines(sheet: &ResultSheet) -> Vec<String> {
+    let rows = sheet
+        .cells
+        .iter()
+        .map(|cell| cell.row + 1)
+        .max()
+        .unwrap_or(0);
+    let columns = sheet
+        .cells
+        .iter()
+        .map(|cell| cell.column as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut grid = vec![vec![String::new(); columns]; rows as usize];
+    for cell in &sheet.cells {
+        grid[cell.row as usize][cell.column as usize] = escape_cell(&cell_text(&cell.value));
+    }
+    std::iter::once(escape_cell(&sheet.name))
+        .chain(grid.into_iter().map(|row| row.join(SEPARATOR)))
+        .collect()
+}
+
+/// The results workbook as one CSV document, each sheet preceded by its name
+/// and separated from the next by a blank line.
+pub fn build_csv(sheets: &[ResultSheet]) -> String {
+    let blocks: Vec<String> = sheets
+        .iter()
+        .map(|sheet| sheet_lines(sheet).join(LINE_BREAK))
+        .collect();
+    format!(
+        "{}{}{}",
+        BOM,
+        blocks.join(&format!("{}{}", LINE_BREAK, LINE_BREAK)),
+        LINE_BREAK
+    )
+}
+
+/// Rows as one CSV document, in the same layout as the results export.
+pub fn build_table(rows: &[Vec<String>]) -> String {
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| escape_cell(cell))
+                .collect::<Vec<_>>()
+                .join(SEPARATOR)
+        })
+        .collect();
+    format!("{}{}{}", BOM, lines.join(LINE_BREAK), LINE_BREAK)
+}
+
+pub fn export_results_csv(
+    data: serde_json::Value,
+    file_path: &str,
+    labels: HashMap<String, String>,
+) -> Result<(), String> {
+    let model = ResultsModel::from_project(data)?;
+    let sheets = results_workbook::build_sheets(&model, &Labels(labels));
+    json_io::write_atomic(
+        Path::new(file_path),
+        build_csv(&sheets).as_bytes(),
+        "export results CSV",
+    )
+}
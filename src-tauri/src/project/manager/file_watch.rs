@@ -0,0 +1,273 @@
+use lazy_static::lazy_static;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+
+use super::paths;
+use super::types::ExternalFileChange;
+
+pub const PROJECT_CHANGE_EVENT: &str = "project:external-change";
+pub const BAREMES_CHANGE_EVENT: &str = "baremes:external-change";
+/// Editors and sync clients write in bursts; events are grouped over this delay.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Clone)]
+struct FileState {
+    modified: Option<String>,
+    hash: String,
+}
+
+/// Files currently watched. Writes from this app go through
+/// [`record_write`] first, so they are recognised and not reported back.
+#[derive(Default)]
+struct WatchScope {
+    project: Option<PathBuf>,
+    baremes: Option<PathBuf>,
+    known: HashMap<PathBuf, FileState>,
+}
+
+impl WatchScope {
+    fn scope_of(&self, path: &Path) -> Option<&'static str> {
+        if self.project.as_deref() == Some(path) {
+            return Some("project");
+        }
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let is_json = path.extension().and_then(|e| e.to_str()) == Some("json");
+        if !hidden && is_json && self.baremes.is_some() && path.parent() == self.baremes.as_deref()
+        {
+            return Some("bareme");
+        }
+        None
+    }
+}
+
+lazy_static! {
+    static ref SCOPE: Mutex<WatchScope> = Mutex::new(WatchScope::default());
+    static ref WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+}
+
+/// Absolute form of `path` with a canonical parent, so paths reported by the
+/// OS (symlinked temp dirs, `\\?\` prefixes) compare equal to ours. Works for
+/// files that no longer exist.
+fn normalize(path: &Path) -> PathBuf {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return path.to_path_buf();
+    };
+    match fs::canonicalize(parent) {
+        Ok(parent) => parent.join(name),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn modified_at(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+}
+
+fn read_state(path: &Path) -> Option<FileState> {
+    let bytes = fs::read(path).ok()?;
+    Some(FileState {
+        modified: modified_at(path),
+        hash: hash_bytes(&bytes),
+    })
+}
+
+/// Called before the app writes a watched file, so the change is not reported
+/// as external.
+pub fn record_write(path: &Path, bytes: &[u8]) {
+    let mut scope = SCOPE.lock().unwrap_or_else(|e| e.into_inner());
+    let path = normalize(path);
+    if scope.scope_of(&path).is_some() {
+        scope.known.insert(
+            path,
+            FileState {
+                modified: None,
+                hash: hash_bytes(bytes),
+            },
+        );
+    }
+}
+
+/// Called before the app deletes or moves a watched file.
+pub fn record_removal(path: &Path) {
+    let mut scope = SCOPE.lock().unwrap_or_else(|e| e.into_inner());
+    scope.known.remove(&normalize(path));
+}
+
+fn change(
+    scope: &'static str,
+    kind: &str,
+    path: &Path,
+    old: Option<&FileState>,
+    new: Option<&FileState>,
+) -> ExternalFileChange {
+    ExternalFileChange {
+        scope: scope.to_string(),
+        kind: kind.to_string(),
+        path: path.to_string_lossy().to_string(),
+        new_path: None,
+        old_modified: old.and_then(|state| state.modified.clone()),
+        new_modified: new.and_then(|state| state.modified.clone()),
+        old_hash: old.map(|state| state.hash.clone()),
+        new_hash: new.map(|state| state.hash.clone()),
+    }
+}
+
+/// Compares every touched file with its last known state and returns the
+/// changes this app did not make itself.
+fn collect_changes(events: &[Event]) -> Vec<ExternalFileChange> {
+    let mut touched = BTreeSet::new();
+    let mut renamed_to: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut pending_from: Option<PathBuf> = None;
+    for event in events {
+        let paths: Vec<PathBuf> = event.paths.iter().map(|path| normalize(path)).collect();
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+                renamed_to.insert(paths[0].clone(), paths[1].clone());
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                pending_from = paths.first().cloned();
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                if let (Some(from), Some(to)) = (pending_from.take(), paths.first()) {
+                    renamed_to.insert(from, to.clone());
+                }
+            }
+            EventKind::Access(_) => continue,
+            _ => {}
+        }
+        touched.extend(paths);
+    }
+
+    let mut scope = SCOPE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut changes = Vec::new();
+    for path in touched {
+        let Some(kind_scope) = scope.scope_of(&path) else {
+            continue;
+        };
+        let old = scope.known.get(&path).cloned();
+        match (read_state(&path), old) {
+            (Some(new), Some(old)) if new.hash == old.hash => {
+                scope.known.insert(path, new);
+            }
+            (Some(new), old) => {
+                let kind = if old.is_some() { "modified" } else { "created" };
+                changes.push(change(kind_scope, kind, &path, old.as_ref(), Some(&new)));
+                scope.known.insert(path, new);
+            }
+            (None, Some(old)) => {
+                scope.known.remove(&path);
+                let renamed = renamed_to
+                    .get(&path)
+                    .and_then(|to| Some((to, read_state(to)?)));
+                match renamed {
+                    Some((to, new)) => {
+                        let mut event =
+                            change(kind_scope, "renamed", &path, Some(&old), Some(&new));
+                        event.new_path = Some(to.to_string_lossy().to_string());
+                        changes.push(event);
+                    }
+                    None => changes.push(change(kind_scope, "deleted", &path, Some(&old), None)),
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+fn run_event_loop(app_handle: tauri::AppHandle, rx: mpsc::Receiver<notify::Result<Event>>) {
+    // Ends when the watcher, and with it the sender, is dropped.
+    while let Ok(first) = rx.recv() {
+        let mut events: Vec<Event> = first.into_iter().collect();
+        while let Ok(next) = rx.recv_timeout(DEBOUNCE) {
+            events.extend(next);
+        }
+        for change in collect_changes(&events) {
+            let event = if change.scope == "project" {
+                PROJECT_CHANGE_EVENT
+            } else {
+                BAREMES_CHANGE_EVENT
+            };
+            if let Err(error) = app_handle.emit(event, &change) {
+                eprintln!("[AMV] Failed to emit {}: {}", event, error);
+            }
+        }
+    }
+}
+
+/// Watches the open project file and the barèmes folder, replacing any
+/// previous watch. Passing no project keeps watching the barèmes only.
+pub fn watch(app_handle: tauri::AppHandle, project_path: Option<&Path>) -> Result<(), String> {
+    unwatch();
+
+    let baremes = paths::baremes_folder()
+        .ok()
+        .map(|folder| fs::canonicalize(&folder).unwrap_or(folder));
+    let project = project_path.map(normalize);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+    // Parent folders are watched rather than the file itself: atomic saves
+    // replace the file and would silently end a watch on its old inode.
+    if let Some(folder) = project.as_deref().and_then(Path::parent) {
+        watcher
+            .watch(folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", folder.display(), e))?;
+    }
+    if let Some(folder) = baremes.as_deref() {
+        if project.as_deref().and_then(Path::parent) != Some(folder) {
+            watcher
+                .watch(folder, RecursiveMode::NonRecursive)
+                .map_err(|e| format!("Failed to watch {}: {}", folder.display(), e))?;
+        }
+    }
+
+    let mut known = HashMap::new();
+    if let Some(project) = project.as_deref() {
+        if let Some(state) = read_state(project) {
+            known.insert(project.to_path_buf(), state);
+        }
+    }
+    if let Some(folder) = baremes.as_deref() {
+        for entry in fs::read_dir(folder).into_iter().flatten().flatten() {
+            let path = normalize(&entry.path());
+            if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                if let Some(state) = read_state(&path) {
+                    known.insert(path, state);
+                }
+            }
+        }
+    }
+    *SCOPE.lock().unwrap_or_else(|e| e.into_inner()) = WatchScope {
+        project,
+        baremes,
+        known,
+    };
+
+    std::thread::spawn(move || run_event_loop(app_handle, rx));
+    *WATCHER.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+    Ok(())
+}
+
+pub fn unwatch() {
+    WATCHER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    *SCOPE.lock().unwrap_or_else(|e| e.into_inner()) = WatchScope::default();
+}
@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::json_io;
+use super::project_listing;
+use super::types::{ProjectSnapshot, ProjectSnapshotDiff, SnapshotScoreChange};
+
+const HISTORY_FOLDER_NAME: &str = ".history";
+const SNAPSHOT_TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S%3f";
+/// Most recent saves kept unconditionally.
+const KEEP_LATEST_SNAPSHOTS: usize = 20;
+/// Older saves are thinned out to one per hour, up to this many hours.
+const KEEP_HOURLY_SNAPSHOTS: usize = 72;
+
+/// Snapshots live in `<project folder>/.history/<project file stem>/`, so two
+/// projects in the same folder never share a history.
+fn history_folder(project_path: &Path) -> Result<PathBuf, String> {
+    let parent = project_path
+        .parent()
+        .ok_or_else(|| format!("Invalid project path: {}", project_path.display()))?;
+    let stem = project_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| format!("Invalid project path: {}", project_path.display()))?;
+    Ok(parent.join(HISTORY_FOLDER_NAME).join(stem))
+}
+
+fn snapshot_path(project_path: &Path, snapshot_id: &str) -> Result<PathBuf, String> {
+    let id = snapshot_id.trim();
+    if id.is_empty() || !id.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid snapshot id: {}", snapshot_id));
+    }
+    let path = history_folder(project_path)?.join(format!("{}.json", id));
+    if !path.is_file() {
+        return Err(format!("Snapshot not found: {}", id));
+    }
+    Ok(path)
+}
+
+/// Snapshot ids sorted newest first. Ids are timestamps, so the lexical order
+/// is also the chronological one.
+fn snapshot_ids(folder: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|stem| stem.to_string())
+        })
+        .collect();
+    ids.sort_by(|a, b| b.cmp(a));
+    ids
+}
+
+/// Records a snapshot of freshly saved project data, skipping it when nothing
+/// changed since the previous snapshot, then prunes the history.
+pub fn record_snapshot(project_path: &Path, data: &serde_json::Value) -> Result<(), String> {
+    let folder = history_folder(project_path)?;
+    fs::create_dir_all(&folder).map_err(|e| format!("Failed to create history folder: {}", e))?;
+
+    let ids = snapshot_ids(&folder);
+    if let Some(latest) = ids.first() {
+        let latest_path = folder.join(format!("{}.json", latest));
+        if json_io::read_json(&latest_path, "read snapshot", "parse snapshot")
+            .ok()
+            .as_ref()
+            == Some(data)
+        {
+            return Ok(());
+        }
+    }
+
+    let mut id = chrono::Utc::now()
+        .format(SNAPSHOT_TIMESTAMP_FORMAT)
+        .to_string();
+    // Saves within the same millisecond get a suffix; it keeps the lexical order
+    // chronological, since "…1" sorts after the id it extends.
+    while folder.join(format!("{}.json", id)).exists() {
+        id.push('1');
+    }
+    json_io::write_pretty_json(
+        &folder.join(format!("{}.json", id)),
+        data,
+        "write project snapshot",
+    )?;
+
+    prune_snapshots(&folder);
+    Ok(())
+}
+
+fn prune_snapshots(folder: &Path) {
+    let ids = snapshot_ids(folder);
+    let mut hours_seen = HashSet::new();
+    let mut hourly_kept = 0;
+
+    for (index, id) in ids.iter().enumerate() {
+        let fresh_hour = hours_seen.insert(hour_bucket(id));
+        if index < KEEP_LATEST_SNAPSHOTS {
+            continue;
+        }
+        // Ids are visited newest first, so the survivor of each hour is its last save.
+        if fresh_hour && hourly_kept < KEEP_HOURLY_SNAPSHOTS {
+            hourly_kept += 1;
+            continue;
+        }
+        let _ = fs::remove_file(folder.join(format!("{}.json", id)));
+    }
+}
+
+fn hour_bucket(snapshot_id: &str) -> &str {
+    // yyyymmddHH
+    snapshot_id.get(..10).unwrap_or(snapshot_id)
+}
+
+fn snapshot_created_at(snapshot_id: &str) -> String {
+    let digits: String = snapshot_id.chars().take(17).collect();
+    chrono::NaiveDateTime::parse_from_str(&digits, "%Y%m%d%H%M%S%3f")
+        .map(|date| date.and_utc().to_rfc3339())
+        .unwrap_or_default()
+}
+
+pub fn list_snapshots(file_path: String) -> Result<Vec<ProjectSnapshot>, String> {
+    let folder = history_folder(Path::new(&file_path))?;
+    let mut snapshots = Vec::new();
+
+    for id in snapshot_ids(&folder) {
+        let path = folder.join(format!("{}.json", id));
+        let Ok(data) = json_io::read_json(&path, "read snapshot", "parse snapshot") else {
+            continue;
+        };
+        let (clip_count, scored_count) = project_listing::clip_counts(&data);
+        snapshots.push(ProjectSnapshot {
+            created_at: snapshot_created_at(&id),
+            updated_at: project_listing::project_updated_at(&data),
+            id,
+            clip_count,
+            scored_count,
+            size_bytes: fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+        });
+    }
+
+    Ok(snapshots)
+}
+
+pub fn load_snapshot(file_path: &str, snapshot_id: &str) -> Result<serde_json::Value, String> {
+    let path = snapshot_path(Path::new(file_path), snapshot_id)?;
+    json_io::read_json(&path, "read snapshot", "parse snapshot")
+}
+
+/// Diffs a snapshot against another one, or against the project file on disk
+/// when `against_id` is `None`.
+pub fn diff_snapshot(
+    file_path: String,
+    snapshot_id: String,
+    against_id: Option<String>,
+) -> Result<ProjectSnapshotDiff, String> {
+    let before = load_snapshot(&file_path, &snapshot_id)?;
+    let after = match against_id.as_deref() {
+        Some(id) => load_snapshot(&file_path, id)?,
+        None => json_io::read_json(Path::new(&file_path), "read project", "parse project")?,
+    };
+    Ok(diff_projects(&before, &after))
+}
+
+pub fn clip_names(data: &serde_json::Value) -> BTreeMap<String, String> {
+    project_listing::project_root(data)["clips"]
+        .as_array()
+        .map(|clips| {
+            clips
+                .iter()
+                .filter_map(|clip| {
+                    let id = clip["id"].as_str()?.to_string();
+                    let name = clip["displayName"]
+                        .as_str()
+                        .or_else(|| clip["fileName"].as_str())
+                        .unwrap_or(&id)
+                        .to_string();
+                    Some((id, name))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn score_values(data: &serde_json::Value) -> BTreeMap<(String, String), serde_json::Value> {
+    let mut values = BTreeMap::new();
+    let Some(notes) = project_listing::project_root(data)["notes"].as_object() else {
+        return values;
+    };
+    for (clip_id, note) in notes {
+        let Some(scores) = note["scores"].as_object() else {
+            continue;
+        };
+        for (criterion_id, score) in scores {
+            values.insert(
+                (clip_id.clone(), criterion_id.clone()),
+                score
+                    .get("value")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null),
+            );
+        }
+    }
+    values
+}
+
+fn diff_projects(before: &serde_json::Value, after: &serde_json::Value) -> ProjectSnapshotDiff {
+    let before_clips = clip_names(before);
+    let after_clips = clip_names(after);
+    let before_scores = score_values(before);
+    let after_scores = score_values(after);
+
+    let clips_added = after_clips
+        .iter()
+        .filter(|(id, _)| !before_clips.contains_key(*id))
+        .map(|(_, name)| name.clone())
+        .collect();
+    let clips_removed = before_clips
+        .iter()
+        .filter(|(id, _)| !after_clips.contains_key(*id))
+        .map(|(_, name)| name.clone())
+        .collect();
+
+    let keys: std::collections::BTreeSet<_> =
+        before_scores.keys().chain(after_scores.keys()).collect();
+    let mut score_changes = Vec::new();
+    for key in keys {
+        let old = before_scores
+            .get(key)
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        let new = after_scores
+            .get(key)
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        if old == new {
+            continue;
+        }
+        let (clip_id, criterion_id) = key;
+        score_changes.push(SnapshotScoreChange {
+            clip_name: after_clips
+                .get(clip_id)
+                .or_else(|| before_clips.get(clip_id))
+                .cloned()
+                .unwrap_or_else(|| clip_id.clone()),
+            clip_id: clip_id.clone(),
+            criterion_id: criterion_id.clone(),
+            before: old,
+            after: new,
+        });
+    }
+
+    ProjectSnapshotDiff {
+        before_updated_at: project_listing::project_updated_at(before),
+        after_updated_at: project_listing::project_updated_at(after),
+        clips_added,
+        clips_removed,
+        score_changes,
+    }
+}
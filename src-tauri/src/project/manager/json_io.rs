@@ -1,5 +1,54 @@
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::file_watch;
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Writes `bytes` next to `path`, flushes them to disk and renames the temporary
+/// file over the target, so readers only ever see the old or the new content.
+pub fn write_atomic(path: &Path, bytes: &[u8], context: &str) -> Result<(), String> {
+    file_watch::record_write(path, bytes);
+    let tmp = temp_path(path);
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, path)?;
+        sync_parent_dir(path);
+        Ok::<(), std::io::Error>(())
+    })();
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to {}: {}", context, e)
+    })
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
 
 pub fn write_pretty_json(
     path: &Path,
@@ -7,7 +56,22 @@ pub fn write_pretty_json(
     context: &str,
 ) -> Result<(), String> {
     let json = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
-    fs::write(path, json).map_err(|e| format!("Failed to {}: {}", context, e))
+    write_atomic(path, json.as_bytes(), context)
+}
+
+/// Same as [`write_pretty_json`], but first copies the current file to
+/// `<name>.bak` when it still parses, so a good copy always survives.
+pub fn write_pretty_json_with_backup(
+    path: &Path,
+    data: &serde_json::Value,
+    context: &str,
+) -> Result<(), String> {
+    if let Ok(previous) = fs::read(path) {
+        if serde_json::from_slice::<serde_json::Value>(&previous).is_ok() {
+            write_atomic(&backup_path(path), &previous, "write backup")?;
+        }
+    }
+    write_pretty_json(path, data, context)
 }
 
 pub fn read_json(
@@ -19,3 +83,36 @@ pub fn read_json(
         fs::read_to_string(path).map_err(|e| format!("Failed to {}: {}", read_context, e))?;
     serde_json::from_str(&json).map_err(|e| format!("Failed to {}: {}", parse_context, e))
 }
+
+/// Reads `path` and runs `accept` on it, falling back to the `.bak` copy when
+/// the main file cannot be read or parsed. A file that parses but is refused
+/// by `accept` (too new, invalid) is reported as is: loading the older backup
+/// instead would let the next save overwrite the real file with it.
+pub fn read_json_with_backup<F>(
+    path: &Path,
+    read_context: &str,
+    parse_context: &str,
+    accept: F,
+) -> Result<serde_json::Value, String>
+where
+    F: Fn(serde_json::Value) -> Result<serde_json::Value, String>,
+{
+    match read_json(path, read_context, parse_context) {
+        Ok(value) => accept(value),
+        Err(error) => {
+            let backup = backup_path(path);
+            match read_json(&backup, read_context, parse_context).and_then(&accept) {
+                Ok(value) => {
+                    eprintln!(
+                        "[AMV] {} ({}); recovered from {}",
+                        error,
+                        path.display(),
+                        backup.display()
+                    );
+                    Ok(value)
+                }
+                Err(_) => Err(error),
+            }
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::bareme_revisions;
+use super::project_files;
+use super::project_listing;
+use super::project_validation;
+use super::results;
+use super::types::{
+    BaremeMismatch, ClipInfo, DuplicateJudgeName, ImportedJudgeCriterionScore, ImportedJudgeData,
+    ImportedJudgeNote, JudgeMerge, JudgeMergeConflicts, MergedJudge, PartialClip, ProjectData,
+};
+
+const IMPORTED_JUDGE_FALLBACK: &str = "Juge importe";
+
+fn judge_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn author_key(clip: &ClipInfo) -> String {
+    format!(
+        "{}|{}",
+        clip.author.as_deref().unwrap_or("").to_lowercase(),
+        clip.display_name.to_lowercase()
+    )
+}
+
+fn parse(data: serde_json::Value, context: &str) -> Result<ProjectData, String> {
+    let normalized = project_validation::normalize_project_value(data, context)?;
+    serde_json::from_value(project_listing::project_root(&normalized).clone())
+        .map_err(|e| format!("Invalid {}: {}", context, e))
+}
+
+/// Finds a judge's clip among the target clips, like `normalizeImportedJudge`:
+/// same clip id first, then the clip's file name, then author and title.
+struct ClipMatcher<'a> {
+    ids: HashSet<&'a str>,
+    by_file: HashMap<String, &'a str>,
+    by_author: HashMap<String, &'a str>,
+}
+
+impl<'a> ClipMatcher<'a> {
+    fn new(clips: &'a [ClipInfo]) -> Self {
+        ClipMatcher {
+            ids: clips.iter().map(|clip| clip.id.as_str()).collect(),
+            by_file: clips
+                .iter()
+                .map(|clip| (clip.file_name.to_lowercase(), clip.id.as_str()))
+                .collect(),
+            by_author: clips
+                .iter()
+                .map(|clip| (author_key(clip), clip.id.as_str()))
+                .collect(),
+        }
+    }
+
+    fn find(&self, source_id: &str, source_clip: Option<&ClipInfo>) -> Option<&'a str> {
+        if let Some(id) = self.ids.get(source_id) {
+            return Some(*id);
+        }
+        let clip = source_clip?;
+        self.by_file
+            .get(&clip.file_name.to_lowercase())
+            .or_else(|| self.by_author.get(&author_key(clip)))
+            .copied()
+    }
+}
+
+/// Maps a judge's notes onto the target clips; returns the imported judge and
+/// the file names of the clips that matched none.
+fn imported_judge(judge: &ProjectData, clips: &[ClipInfo]) -> (ImportedJudgeData, Vec<String>) {
+    let matcher = ClipMatcher::new(clips);
+    let source_clips: HashMap<&str, &ClipInfo> = judge
+        .clips
+        .iter()
+        .map(|clip| (clip.id.as_str(), clip))
+        .collect();
+
+    let mut notes = BTreeMap::new();
+    let mut unmatched = Vec::new();
+    for (source_id, note) in &judge.notes {
+        let source_clip = source_clips.get(source_id.as_str()).copied();
+        let Some(target) = matcher.find(source_id, source_clip) else {
+            unmatched
+                .push(source_clip.map_or_else(|| source_id.clone(), |clip| clip.file_name.clone()));
+            continue;
+        };
+        let scores = note
+            .scores
+            .iter()
+            .map(|(criterion_id, score)| {
+                (
+                    criterion_id.clone(),
+                    ImportedJudgeCriterionScore {
+                        value: score.value.clone(),
+                        is_valid: score.is_valid,
+                        extra: score.extra.clone(),
+                    },
+                )
+            })
+            .collect();
+        notes.insert(
+            target.to_string(),
+            ImportedJudgeNote {
+                scores,
+                final_score: note.final_score,
+                text_notes: Some(note.text_notes.clone()),
+                criterion_notes: note.criterion_notes.clone(),
+                category_notes: note.category_notes.clone(),
+                favorite: source_clip.and_then(|clip| clip.favorite),
+                favorite_comment: source_clip.and_then(|clip| clip.favorite_comment.clone()),
+                extra: Default::default(),
+            },
+        );
+    }
+
+    let name = judge.project.judge_name.trim();
+    let judge = ImportedJudgeData {
+        judge_name: if name.is_empty() {
+            IMPORTED_JUDGE_FALLBACK.to_string()
+        } else {
+            name.to_string()
+        },
+        notes,
+        extra: Default::default(),
+    };
+    (judge, unmatched)
+}
+
+/// Barème id and content hash the file's scores were given with; the hash is
+/// empty when the file neither embeds the barème nor pins a revision.
+fn bareme_fingerprint(project: &ProjectData) -> (String, String) {
+    let bareme_id = if project.bareme_id.is_empty() {
+        project.project.bareme_id.clone()
+    } else {
+        project.bareme_id.clone()
+    };
+    let embedded = project
+        .extra
+        .get("bareme")
+        .filter(|bareme| bareme["criteria"].is_array())
+        .filter(|bareme| bareme["id"].as_str().map_or(true, |id| id == bareme_id));
+    let content_hash = match (embedded, &project.bareme_revision) {
+        (Some(bareme), _) => bareme_revisions::content_hash(bareme),
+        (None, Some(pin)) if pin.bareme_id == bareme_id => pin.content_hash.clone(),
+        _ => String::new(),
+    };
+    (bareme_id, content_hash)
+}
+
+fn bareme_differs(expected: &(String, String), found: &(String, String)) -> bool {
+    expected.0 != found.0
+        || (!expected.1.is_empty() && !found.1.is_empty() && expected.1 != found.1)
+}
+
+/// Clips that at least one judge scored and at least one did not.
+fn partial_clips(project: &ProjectData) -> Vec<PartialClip> {
+    project
+        .clips
+        .iter()
+        .filter_map(|clip| {
+            let (scored_by, missing): (Vec<&ImportedJudgeData>, Vec<&ImportedJudgeData>) =
+                project.imported_judges.iter().partition(|judge| {
+                    judge
+                        .notes
+                        .get(&clip.id)
+                        .is_some_and(|note| !note.scores.is_empty())
+                });
+            if scored_by.is_empty() || missing.is_empty() {
+                return None;
+            }
+            let names = |judges: Vec<&ImportedJudgeData>| {
+                judges.iter().map(|j| j.judge_name.clone()).collect()
+            };
+            Some(PartialClip {
+                clip_id: clip.id.clone(),
+                label: results::clip_label(
+                    clip.author.as_deref(),
+                    &clip.display_name,
+                    &clip.file_name,
+                ),
+                scored_by: names(scored_by),
+                missing: names(missing),
+            })
+        })
+        .collect()
+}
+
+/// First free "Name (n)" for a judge whose name is already taken.
+fn unique_judge_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|index| format!("{} ({})", name, index))
+        .find(|candidate| !taken.contains(&judge_key(candidate)))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Adds the judges to `project`'s imported judges. An imported judge with the
+/// same name as an incoming one is replaced, as on a re-import; two incoming
+/// files with the same judge name are both kept, the second renamed, and
+/// reported. With `add_missing_clips`, clips only a judge has are added to
+/// the project instead of being reported as unmatched.
+fn merge_into(
+    mut project: ProjectData,
+    judges: Vec<(String, ProjectData)>,
+    add_missing_clips: bool,
+) -> Result<JudgeMerge, String> {
+    let expected = bareme_fingerprint(&project);
+    let mut merged = Vec::new();
+    let mut incoming: Vec<ImportedJudgeData> = Vec::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut duplicates: Vec<DuplicateJudgeName> = Vec::new();
+    let mut bareme_mismatches = Vec::new();
+
+    for (source, judge) in judges {
+        if add_missing_clips {
+            let missing: Vec<ClipInfo> = {
+                let matcher = ClipMatcher::new(&project.clips);
+                judge
+                    .clips
+                    .iter()
+                    .filter(|clip| matcher.find(&clip.id, Some(clip)).is_none())
+                    .cloned()
+                    .collect()
+            };
+            let next_order = project
+                .clips
+                .iter()
+                .map(|clip| clip.order + 1)
+                .max()
+                .unwrap_or(0);
+            for (offset, mut clip) in missing.into_iter().enumerate() {
+                clip.order = next_order + offset as u32;
+                clip.scored = false;
+                clip.favorite = None;
+                clip.favorite_comment = None;
+                project.clips.push(clip);
+            }
+        }
+
+        let (mut imported, unmatched) = imported_judge(&judge, &project.clips);
+        let found = bareme_fingerprint(&judge);
+        if bareme_differs(&expected, &found) {
+            bareme_mismatches.push(BaremeMismatch {
+                source: source.clone(),
+                judge_name: imported.judge_name.clone(),
+                bareme_id: found.0,
+                content_hash: found.1,
+                expected_bareme_id: expected.0.clone(),
+                expected_content_hash: expected.1.clone(),
+            });
+        }
+
+        let key = judge_key(&imported.judge_name);
+        if taken.contains(&key) {
+            let renamed = unique_judge_name(&imported.judge_name, &taken);
+            match duplicates
+                .iter_mut()
+                .find(|d| judge_key(&d.judge_name) == key)
+            {
+                Some(duplicate) => {
+                    duplicate.sources.push(source.clone());
+                    duplicate.renamed_to.push(renamed.clone());
+                }
+                None => {
+                    let first = merged
+                        .iter()
+                        .find(|judge: &&MergedJudge| judge_key(&judge.judge_name) == key)
+                        .map(|judge| judge.source.clone());
+                    duplicates.push(DuplicateJudgeName {
+                        judge_name: imported.judge_name.clone(),
+                        sources: first.into_iter().chain([source.clone()]).collect(),
+                        renamed_to: vec![renamed.clone()],
+                    });
+                }
+            }
+            imported.judge_name = renamed;
+        }
+        taken.insert(judge_key(&imported.judge_name));
+
+        let matched_clips = imported.notes.len();
+        merged.push(MergedJudge {
+            source,
+            judge_name: imported.judge_name.clone(),
+            matched_clips,
+            unmatched_clips: unmatched,
+        });
+        if matched_clips > 0 {
+            incoming.push(imported);
+        }
+    }
+
+    project
+        .imported_judges
+        .retain(|judge| !taken.contains(&judge_key(&judge.judge_name)));
+    project.imported_judges.extend(incoming);
+    let conflicts = JudgeMergeConflicts {
+        duplicate_judges: duplicates,
+        bareme_mismatches,
+        partial_clips: partial_clips(&project),
+    };
+    Ok(JudgeMerge {
+        project: serde_json::to_value(project).map_err(|e| e.to_string())?,
+        judges: merged,
+        conflicts,
+    })
+}
+
+fn read_judges(file_paths: Vec<String>) -> Result<Vec<(String, ProjectData)>, String> {
+    file_paths
+        .into_iter()
+        .map(|path| {
+            let value = project_files::read_project_file(path.clone())?;
+            let judge = parse(value, &format!("judge file {}", path))?;
+            Ok((path, judge))
+        })
+        .collect()
+}
+
+/// Reads judge project files without locking them and adds them to the
+/// imported judges of `data`.
+pub fn merge_judge_projects(
+    data: serde_json::Value,
+    file_paths: Vec<String>,
+) -> Result<JudgeMerge, String> {
+    let project = parse(data, "project")?;
+    merge_into(project, read_judges(file_paths)?, false)
+}
+
+/// Builds a results project from judge project files of the same contest:
+/// the first file gives the contest settings, clips and barème, and every
+/// file becomes an imported judge. Nothing is saved.
+pub fn merge_judge_files(
+    file_paths: Vec<String>,
+    name: Option<String>,
+) -> Result<JudgeMerge, String> {
+    let judges = read_judges(file_paths)?;
+    let Some((_, first)) = judges.first() else {
+        return Err("No judge file to merge".to_string());
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut project = first.clone();
+    project.project.id = uuid::Uuid::new_v4().to_string();
+    if let Some(name) = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+    {
+        project.project.name = name;
+    }
+    project.project.judge_name = String::new();
+    project.project.created_at = now.clone();
+    project.project.updated_at = now;
+    project.project.file_path = None;
+    project.project.result_notes.clear();
+    project.notes.clear();
+    project.imported_judges.clear();
+    for clip in &mut project.clips {
+        clip.scored = false;
+        clip.favorite = None;
+        clip.favorite_comment = None;
+    }
+    merge_into(project, judges, true)
+}
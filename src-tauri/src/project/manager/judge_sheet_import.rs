@@ -0,0 +1,499 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use super::bareme_import::{self, SheetHeader};
+use super::baremes;
+use super::project_listing;
+use super::project_search::fold;
+use super::spreadsheet::{self, SheetRows};
+use super::types::{
+    ImportedJudgeCriterionScore, ImportedJudgeData, ImportedJudgeNote, JudgeSheetClipCandidate,
+    JudgeSheetColumn, JudgeSheetImport, JudgeSheetRow, JudgeSheetValueIssue, ScoreValue,
+};
+
+/// Below this a row is reported as unmatched rather than guessed.
+const MATCH_THRESHOLD: u8 = 75;
+/// A runner-up this close to the best clip makes the row ambiguous.
+const AMBIGUITY_MARGIN: u8 = 10;
+const MAX_REPORTED_CANDIDATES: usize = 3;
+const EPSILON: f64 = 1e-6;
+
+/// Lowercase, accent-free words; punctuation such as `&`, `-` or `,` between
+/// co-authors is dropped.
+fn words(value: &str) -> Vec<String> {
+    fold(value)
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// 0-100 similarity of a sheet label and one name of a clip. Whole-word
+/// containment covers "DarkVoodoo" against "DarkVoodoo - Fallen" and
+/// "Elixir & SliceofLife - Start Again" against the author alone.
+fn similarity(label: &[String], key: &[String]) -> u8 {
+    if label.is_empty() || key.is_empty() {
+        return 0;
+    }
+    if label == key {
+        return 100;
+    }
+    let label_set: HashSet<&String> = label.iter().collect();
+    let key_set: HashSet<&String> = key.iter().collect();
+    if label_set.is_subset(&key_set) {
+        return 90;
+    }
+    if key_set.is_subset(&label_set) && key.concat().chars().count() >= 3 {
+        return 85;
+    }
+    let (a, b) = (label.concat(), key.concat());
+    let longest = a.chars().count().max(b.chars().count());
+    let ratio = 1.0 - levenshtein(&a, &b) as f64 / longest as f64;
+    (ratio * 100.0).round().clamp(0.0, 99.0) as u8
+}
+
+struct ClipKeys {
+    id: String,
+    display_name: String,
+    author: Option<String>,
+    keys: Vec<Vec<String>>,
+}
+
+fn clip_keys(data: &serde_json::Value) -> Vec<ClipKeys> {
+    let clips = project_listing::project_root(data)["clips"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    clips
+        .iter()
+        .filter_map(|clip| {
+            let id = clip["id"].as_str().filter(|id| !id.is_empty())?.to_string();
+            let display_name = clip["displayName"].as_str().unwrap_or("").to_string();
+            let author = clip["author"]
+                .as_str()
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string);
+            let file_stem = Path::new(clip["fileName"].as_str().unwrap_or(""))
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut keys = vec![words(&display_name), words(&file_stem)];
+            if let Some(author) = &author {
+                keys.push(words(author));
+                keys.push(words(&format!("{} {}", author, display_name)));
+            }
+            keys.retain(|key| !key.is_empty());
+            Some(ClipKeys {
+                id,
+                display_name,
+                author,
+                keys,
+            })
+        })
+        .collect()
+}
+
+fn rank_clips(label: &str, clips: &[ClipKeys]) -> Vec<JudgeSheetClipCandidate> {
+    let label = words(label);
+    let mut candidates: Vec<JudgeSheetClipCandidate> = clips
+        .iter()
+        .map(|clip| JudgeSheetClipCandidate {
+            clip_id: clip.id.clone(),
+            display_name: clip.display_name.clone(),
+            author: clip.author.clone(),
+            score: clip
+                .keys
+                .iter()
+                .map(|key| similarity(&label, key))
+                .max()
+                .unwrap_or(0),
+        })
+        .filter(|candidate| candidate.score > 0)
+        .collect();
+    candidates.sort_by(|a, b| b.score.cmp(&a.score).then(a.clip_id.cmp(&b.clip_id)));
+    candidates.truncate(MAX_REPORTED_CANDIDATES);
+    candidates
+}
+
+/// Barème criterion a sheet column maps to.
+struct TargetCriterion {
+    id: String,
+    name: String,
+    min: f64,
+    max: Option<f64>,
+}
+
+fn project_bareme(data: &serde_json::Value) -> Option<serde_json::Value> {
+    let root = project_listing::project_root(data);
+    if root["bareme"]["criteria"].is_array() {
+        return Some(root["bareme"].clone());
+    }
+    let bareme_id = root["baremeId"]
+        .as_str()
+        .or_else(|| root["project"]["baremeId"].as_str())?;
+    baremes::find_bareme(bareme_id)
+}
+
+/// Maps sheet columns to the project's barème by name, then by id, then by
+/// position when the sheet has exactly the barème's criteria. Without a
+/// barème the columns keep the ids a sheet-imported barème would give them.
+fn map_columns(
+    header: &SheetHeader,
+    bareme: Option<&serde_json::Value>,
+) -> Vec<(JudgeSheetColumn, Option<TargetCriterion>)> {
+    let targets: Vec<TargetCriterion> = match bareme {
+        Some(bareme) => bareme["criteria"]
+            .as_array()
+            .map(|criteria| {
+                criteria
+                    .iter()
+                    .filter(|criterion| {
+                        matches!(
+                            criterion["type"].as_str().unwrap_or("numeric"),
+                            "numeric" | "slider"
+                        )
+                    })
+                    .map(|criterion| TargetCriterion {
+                        id: criterion["id"].as_str().unwrap_or("").to_string(),
+                        name: criterion["name"].as_str().unwrap_or("").to_string(),
+                        min: criterion["min"].as_f64().unwrap_or(0.0),
+                        max: criterion["max"].as_f64(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        None => header
+            .criteria
+            .iter()
+            .map(|column| TargetCriterion {
+                id: bareme_import::slug(&column.name),
+                name: column.name.clone(),
+                min: 0.0,
+                max: Some(column.max),
+            })
+            .collect(),
+    };
+
+    let mut used = HashSet::new();
+    let mut picks: Vec<Option<usize>> = header
+        .criteria
+        .iter()
+        .map(|column| {
+            let name = words(&column.name);
+            let slug = bareme_import::slug(&column.name);
+            let pick = targets
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !used.contains(index))
+                .find(|(_, target)| words(&target.name) == name || target.id == slug)
+                .map(|(index, _)| index);
+            if let Some(index) = pick {
+                used.insert(index);
+            }
+            pick
+        })
+        .collect();
+
+    let same_shape = targets.len() == header.criteria.len()
+        && targets
+            .iter()
+            .zip(&header.criteria)
+            .all(|(target, column)| {
+                target
+                    .max
+                    .map_or(true, |max| (max - column.max).abs() < EPSILON)
+            });
+    for (index, pick) in picks.iter_mut().enumerate() {
+        if pick.is_none() && same_shape && !used.contains(&index) {
+            used.insert(index);
+            *pick = Some(index);
+        }
+    }
+    for (column, pick) in header.criteria.iter().zip(picks.iter_mut()) {
+        if pick.is_some() {
+            continue;
+        }
+        let name = words(&column.name);
+        let best = targets
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !used.contains(index))
+            .map(|(index, target)| (index, similarity(&name, &words(&target.name))))
+            .filter(|(_, score)| *score >= MATCH_THRESHOLD)
+            .max_by_key(|(_, score)| *score);
+        if let Some((index, _)) = best {
+            used.insert(index);
+            *pick = Some(index);
+        }
+    }
+
+    let mut targets: Vec<Option<TargetCriterion>> = targets.into_iter().map(Some).collect();
+    header
+        .criteria
+        .iter()
+        .zip(picks)
+        .map(|(column, pick)| {
+            let target = pick.and_then(|index| targets[index].take());
+            (
+                JudgeSheetColumn {
+                    column_name: column.name.clone(),
+                    sheet_max: column.max,
+                    criterion_id: target.as_ref().map(|target| target.id.clone()),
+                    criterion_name: target.as_ref().map(|target| target.name.clone()),
+                },
+                target,
+            )
+        })
+        .collect()
+}
+
+fn judge_name_from_path(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+struct SheetRowScores {
+    row: usize,
+    label: String,
+    values: Vec<(usize, String)>,
+}
+
+fn sheet_rows(rows: &SheetRows, header: &SheetHeader) -> Vec<SheetRowScores> {
+    let label_column = header
+        .criteria
+        .first()
+        .map_or(0, |criterion| criterion.column.saturating_sub(1));
+    let first_row = header.criteria_row + 1;
+    bareme_import::score_rows(rows, header)
+        .iter()
+        .enumerate()
+        .map(|(offset, row)| SheetRowScores {
+            row: first_row + offset + 1,
+            label: spreadsheet::cell(row, label_column).to_string(),
+            values: header
+                .criteria
+                .iter()
+                .enumerate()
+                .map(|(index, criterion)| {
+                    (index, spreadsheet::cell(row, criterion.column).to_string())
+                })
+                .filter(|(_, value)| !value.is_empty())
+                .collect(),
+        })
+        .collect()
+}
+
+/// First sheet with a score header. A results workbook exported from the app
+/// has one sheet per judge after the rankings, so a sheet named after
+/// `judge_name`, or headed `Juge: <judge_name>`, wins over the first one.
+fn pick_sheet(
+    sheets: Vec<(String, SheetRows)>,
+    judge_name: Option<&str>,
+) -> Option<(SheetRows, SheetHeader)> {
+    let wanted = judge_name.map(fold).filter(|name| !name.is_empty());
+    let mut scored: Vec<(String, SheetRows, SheetHeader)> = sheets
+        .into_iter()
+        .filter_map(|(name, rows)| {
+            let header = bareme_import::detect_header(&rows)?;
+            Some((name, rows, header))
+        })
+        .collect();
+    let position = wanted
+        .and_then(|wanted| {
+            scored.iter().position(|(name, _, header)| {
+                fold(name) == *wanted
+                    || header
+                        .judge_name
+                        .as_deref()
+                        .is_some_and(|judge| fold(judge) == *wanted)
+            })
+        })
+        .unwrap_or(0);
+    (position < scored.len()).then(|| {
+        let (_, rows, header) = scored.swap_remove(position);
+        (rows, header)
+    })
+}
+
+/// Reads a judge's score sheet into the `ImportedJudgeData` shape without
+/// touching the project. Rows are matched to clips by author or display name;
+/// `row_matches` (sheet row -> clip id) settles rows the report flagged.
+pub fn import_judge_sheet(
+    file_path: &str,
+    data: &serde_json::Value,
+    judge_name: Option<String>,
+    row_matches: HashMap<usize, String>,
+) -> Result<JudgeSheetImport, String> {
+    let path = Path::new(file_path);
+    let (rows, header) = pick_sheet(spreadsheet::read_sheets(path)?, judge_name.as_deref())
+        .ok_or_else(|| {
+            "No score header found: expected a row of \"Criterion /max\" cells".to_string()
+        })?;
+    let judge_name = judge_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| header.judge_name.clone())
+        .unwrap_or_else(|| judge_name_from_path(path));
+
+    let bareme = project_bareme(data);
+    let columns = map_columns(&header, bareme.as_ref());
+    let clips = clip_keys(data);
+    let clip_ids: HashSet<&str> = clips.iter().map(|clip| clip.id.as_str()).collect();
+
+    let mut report = JudgeSheetImport {
+        judge: None,
+        judge_name: judge_name.clone(),
+        columns: columns.iter().map(|(column, _)| column.clone()).collect(),
+        matched: Vec::new(),
+        unmatched: Vec::new(),
+        ambiguous: Vec::new(),
+        value_issues: Vec::new(),
+    };
+
+    // Best row per clip; a weaker row claiming the same clip becomes ambiguous.
+    let mut claims: HashMap<String, (u8, JudgeSheetRow, SheetRowScores)> = HashMap::new();
+    for sheet_row in sheet_rows(&rows, &header) {
+        let candidates = rank_clips(&sheet_row.label, &clips);
+        let forced = row_matches
+            .get(&sheet_row.row)
+            .filter(|clip_id| clip_ids.contains(clip_id.as_str()))
+            .cloned();
+        let mut entry = JudgeSheetRow {
+            row: sheet_row.row,
+            label: sheet_row.label.clone(),
+            clip_id: None,
+            candidates,
+        };
+
+        let (clip_id, score) = if let Some(clip_id) = forced {
+            (clip_id, 100)
+        } else {
+            let best = entry
+                .candidates
+                .first()
+                .map(|c| (c.clip_id.clone(), c.score));
+            let runner_up = entry.candidates.get(1).map_or(0, |c| c.score);
+            match best {
+                Some((_, score)) if score < MATCH_THRESHOLD => {
+                    report.unmatched.push(entry);
+                    continue;
+                }
+                None => {
+                    report.unmatched.push(entry);
+                    continue;
+                }
+                Some((_, score))
+                    if runner_up >= MATCH_THRESHOLD && score - runner_up < AMBIGUITY_MARGIN =>
+                {
+                    report.ambiguous.push(entry);
+                    continue;
+                }
+                Some(best) => best,
+            }
+        };
+
+        entry.clip_id = Some(clip_id.clone());
+        match claims.remove(&clip_id) {
+            Some(previous) if previous.0 >= score => {
+                let mut loser = entry;
+                loser.clip_id = None;
+                report.ambiguous.push(loser);
+                claims.insert(clip_id, previous);
+            }
+            Some((_, mut previous, _)) => {
+                previous.clip_id = None;
+                report.ambiguous.push(previous);
+                claims.insert(clip_id, (score, entry, sheet_row));
+            }
+            None => {
+                claims.insert(clip_id, (score, entry, sheet_row));
+            }
+        }
+    }
+
+    let mut notes = BTreeMap::new();
+    let mut claimed: Vec<_> = claims.into_iter().collect();
+    claimed.sort_by_key(|(_, (_, entry, _))| entry.row);
+    for (clip_id, (_, entry, sheet_row)) in claimed {
+        let mut scores = BTreeMap::new();
+        let mut total = 0.0;
+        for (index, raw) in &sheet_row.values {
+            let Some(target) = &columns[*index].1 else {
+                continue;
+            };
+            let issue = |message: String| JudgeSheetValueIssue {
+                row: sheet_row.row,
+                label: sheet_row.label.clone(),
+                criterion_id: target.id.clone(),
+                value: raw.clone(),
+                message,
+            };
+            let Some(value) = spreadsheet::parse_decimal(raw) else {
+                report
+                    .value_issues
+                    .push(issue(format!("\"{}\" is not a number", raw)));
+                continue;
+            };
+            let max = target.max.unwrap_or(f64::INFINITY);
+            let is_valid = value + EPSILON >= target.min && value <= max + EPSILON;
+            if is_valid {
+                total += value;
+            } else {
+                report.value_issues.push(issue(format!(
+                    "{} is outside {}–{} for {}",
+                    value, target.min, max, target.name
+                )));
+            }
+            scores.insert(
+                target.id.clone(),
+                ImportedJudgeCriterionScore {
+                    value: ScoreValue::Number(value),
+                    is_valid,
+                    extra: Default::default(),
+                },
+            );
+        }
+        if !scores.is_empty() {
+            notes.insert(
+                clip_id,
+                ImportedJudgeNote {
+                    scores,
+                    final_score: Some((total * 100.0).round() / 100.0),
+                    text_notes: None,
+                    criterion_notes: None,
+                    category_notes: None,
+                    favorite: None,
+                    favorite_comment: None,
+                    extra: Default::default(),
+                },
+            );
+        }
+        report.matched.push(entry);
+    }
+    report.ambiguous.sort_by_key(|entry| entry.row);
+
+    if !notes.is_empty() {
+        report.judge = Some(ImportedJudgeData {
+            judge_name,
+            notes,
+            extra: Default::default(),
+        });
+    }
+    Ok(report)
+}
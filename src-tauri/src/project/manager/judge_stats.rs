@@ -0,0 +1,274 @@
+use super::aggregation::{self, fractional_ranks, round_to};
+use super::results::{self, ResultsModel};
+use super::types::{
+    CriterionAgreement, JudgeOutlier, JudgePairAgreement, JudgeSpread, JudgeStatistics,
+};
+
+/// Default outlier threshold, as a share of the barème total: 9 points on /60.
+const DEFAULT_THRESHOLD_SHARE: f64 = 0.15;
+const EPSILON: f64 = 1e-9;
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    // A judge who gave every common clip the same score has no ranking.
+    (var_x > EPSILON && var_y > EPSILON).then(|| covariance / (var_x * var_y).sqrt())
+}
+
+/// Spearman's rho: Pearson on fractional ranks, so ties are handled.
+fn spearman(pairs: &[(f64, f64)]) -> Option<f64> {
+    let xs: Vec<Option<f64>> = pairs.iter().map(|(x, _)| Some(*x)).collect();
+    let ys: Vec<Option<f64>> = pairs.iter().map(|(_, y)| Some(*y)).collect();
+    let ranked: Vec<(f64, f64)> = fractional_ranks(&xs)
+        .into_iter()
+        .zip(fractional_ranks(&ys))
+        .filter_map(|(x, y)| Some((x?, y?)))
+        .collect();
+    pearson(&ranked)
+}
+
+/// Kendall's tau-b, which corrects for ties on either side.
+fn kendall(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let (mut concordant, mut discordant, mut ties_x, mut ties_y) = (0.0_f64, 0.0, 0.0, 0.0);
+    for (index, (x1, y1)) in pairs.iter().enumerate() {
+        for (x2, y2) in &pairs[index + 1..] {
+            let dx = x1 - x2;
+            let dy = y1 - y2;
+            let tied_x = dx.abs() < EPSILON;
+            let tied_y = dy.abs() < EPSILON;
+            match (tied_x, tied_y) {
+                (true, true) => {}
+                (true, false) => ties_x += 1.0,
+                (false, true) => ties_y += 1.0,
+                _ if dx.signum() == dy.signum() => concordant += 1.0,
+                _ => discordant += 1.0,
+            }
+        }
+    }
+    let denominator =
+        ((concordant + discordant + ties_x) * (concordant + discordant + ties_y)).sqrt();
+    (denominator > EPSILON).then(|| (concordant - discordant) / denominator)
+}
+
+/// ICC(2,1): two-way random effects, absolute agreement, single judge, over
+/// the clips every judge scored. `rows` is clip x judge.
+fn icc(rows: &[Vec<f64>]) -> Option<f64> {
+    let n = rows.len();
+    let k = rows.first()?.len();
+    if n < 2 || k < 2 {
+        return None;
+    }
+    let (n_f, k_f) = (n as f64, k as f64);
+    let grand = rows.iter().flatten().sum::<f64>() / (n_f * k_f);
+    let row_means: Vec<f64> = rows
+        .iter()
+        .map(|row| row.iter().sum::<f64>() / k_f)
+        .collect();
+    let column_means: Vec<f64> = (0..k)
+        .map(|column| rows.iter().map(|row| row[column]).sum::<f64>() / n_f)
+        .collect();
+
+    let ss_rows = k_f
+        * row_means
+            .iter()
+            .map(|mean| (mean - grand).powi(2))
+            .sum::<f64>();
+    let ss_columns = n_f
+        * column_means
+            .iter()
+            .map(|mean| (mean - grand).powi(2))
+            .sum::<f64>();
+    let ss_total = rows
+        .iter()
+        .flatten()
+        .map(|value| (value - grand).powi(2))
+        .sum::<f64>();
+    let ss_error = ss_total - ss_rows - ss_columns;
+
+    let ms_rows = ss_rows / (n_f - 1.0);
+    let ms_columns = ss_columns / (k_f - 1.0);
+    let ms_error = ss_error / ((n_f - 1.0) * (k_f - 1.0));
+    let denominator = ms_rows + (k_f - 1.0) * ms_error + k_f * (ms_columns - ms_error) / n_f;
+    (denominator.abs() > EPSILON).then(|| (ms_rows - ms_error) / denominator)
+}
+
+/// Clip x judge matrix restricted to the clips every kept judge scored. Judges
+/// with fewer than half the scores of the most complete judge are left out,
+/// so one judge who barely started does not empty the matrix. Returns the
+/// rows and the number of judges kept.
+fn complete_rows(matrix: &[Vec<Option<f64>>]) -> (Vec<Vec<f64>>, usize) {
+    let judge_count = matrix.first().map_or(0, |row| row.len());
+    let counts: Vec<usize> = (0..judge_count)
+        .map(|judge| matrix.iter().filter(|row| row[judge].is_some()).count())
+        .collect();
+    let most = counts.iter().copied().max().unwrap_or(0);
+    let kept: Vec<usize> = (0..judge_count)
+        .filter(|judge| counts[*judge] > 0 && counts[*judge] * 2 >= most)
+        .collect();
+    let rows = matrix
+        .iter()
+        .filter_map(|row| kept.iter().map(|judge| row[*judge]).collect())
+        .collect();
+    (rows, kept.len())
+}
+
+/// Per-judge spread, pairwise rank agreement, ICC per criterion and clips
+/// where a judge's total is more than `threshold` points from the mean of the
+/// other judges.
+pub fn judge_statistics_for(model: &ResultsModel, threshold: Option<f64>) -> JudgeStatistics {
+    let threshold = threshold
+        .filter(|threshold| *threshold > 0.0)
+        .unwrap_or(model.total_points * DEFAULT_THRESHOLD_SHARE);
+    // Judge x clip totals.
+    let totals: Vec<Vec<Option<f64>>> = model
+        .judges
+        .iter()
+        .map(|judge| {
+            model
+                .clips
+                .iter()
+                .map(|clip| model.total(judge, &clip.id))
+                .collect()
+        })
+        .collect();
+
+    let others_mean = |judge: usize, clip: usize| {
+        let others: Vec<f64> = totals
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != judge)
+            .filter_map(|(_, row)| row[clip])
+            .collect();
+        results::mean(&others)
+    };
+
+    let judges = model
+        .judges
+        .iter()
+        .zip(&totals)
+        .enumerate()
+        .map(|(index, (judge, row))| {
+            let scored: Vec<f64> = row.iter().flatten().copied().collect();
+            let spread = aggregation::spread(&scored);
+            let offsets: Vec<f64> = row
+                .iter()
+                .enumerate()
+                .filter_map(|(clip, value)| Some(value.as_ref()? - others_mean(index, clip)?))
+                .collect();
+            JudgeSpread {
+                key: judge.key.clone(),
+                name: judge.name.clone(),
+                scored_clips: scored.len(),
+                mean: spread.map(|(mean, _)| results::round_score(mean)),
+                std_dev: spread.map(|(_, std_dev)| results::round_score(std_dev)),
+                min: scored.iter().copied().reduce(f64::min),
+                max: scored.iter().copied().reduce(f64::max),
+                mean_offset: results::mean(&offsets).map(results::round_score),
+            }
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for first in 0..model.judges.len() {
+        for second in first + 1..model.judges.len() {
+            let common: Vec<(f64, f64)> = totals[first]
+                .iter()
+                .zip(&totals[second])
+                .filter_map(|(a, b)| Some(((*a)?, (*b)?)))
+                .collect();
+            pairs.push(JudgePairAgreement {
+                first_key: model.judges[first].key.clone(),
+                first_name: model.judges[first].name.clone(),
+                second_key: model.judges[second].key.clone(),
+                second_name: model.judges[second].name.clone(),
+                common_clips: common.len(),
+                spearman: spearman(&common).map(|value| round_to(value, 3)),
+                kendall: kendall(&common).map(|value| round_to(value, 3)),
+            });
+        }
+    }
+
+    let criteria = model
+        .criteria
+        .iter()
+        .map(|criterion| {
+            let matrix: Vec<Vec<Option<f64>>> = model
+                .clips
+                .iter()
+                .map(|clip| {
+                    model
+                        .judges
+                        .iter()
+                        .map(|judge| model.score(judge, &clip.id, criterion))
+                        .collect()
+                })
+                .collect();
+            let (rows, judges) = complete_rows(&matrix);
+            CriterionAgreement {
+                criterion_id: criterion.id.clone(),
+                name: criterion.name.clone(),
+                clips: rows.len(),
+                judges,
+                icc: icc(&rows).map(|value| round_to(value, 3)),
+            }
+        })
+        .collect();
+    let total_matrix: Vec<Vec<Option<f64>>> = (0..model.clips.len())
+        .map(|clip| totals.iter().map(|row| row[clip]).collect())
+        .collect();
+    let (total_rows, _) = complete_rows(&total_matrix);
+
+    let mut outliers = Vec::new();
+    for (clip_index, clip) in model.clips.iter().enumerate() {
+        for (judge_index, judge) in model.judges.iter().enumerate() {
+            let Some(points) = totals[judge_index][clip_index] else {
+                continue;
+            };
+            let Some(others) = others_mean(judge_index, clip_index) else {
+                continue;
+            };
+            let deviation = points - others;
+            if deviation.abs() > threshold {
+                outliers.push(JudgeOutlier {
+                    clip_id: clip.id.clone(),
+                    label: clip.label.clone(),
+                    judge_key: judge.key.clone(),
+                    judge_name: judge.name.clone(),
+                    points,
+                    others_mean: results::round_score(others),
+                    deviation: results::round_score(deviation),
+                });
+            }
+        }
+    }
+
+    JudgeStatistics {
+        threshold: results::round_score(threshold),
+        judges,
+        pairs,
+        criteria,
+        total_icc: icc(&total_rows).map(|value| round_to(value, 3)),
+        outliers,
+    }
+}
+
+pub fn judge_statistics(
+    data: serde_json::Value,
+    threshold: Option<f64>,
+) -> Result<JudgeStatistics, String> {
+    let model = ResultsModel::from_project(data)?;
+    Ok(judge_statistics_for(&model, threshold))
+}
@@ -0,0 +1,208 @@
+use serde_json::{Map, Value};
+
+/// Schema version written by this build. Files without a `version` field are
+/// treated as `0.0`, the snake_case format of the first releases.
+pub const CURRENT_PROJECT_VERSION: (u32, u32) = (1, 0);
+const MIGRATION_HISTORY_KEY: &str = "migrationHistory";
+
+struct Migration {
+    id: &'static str,
+    from: (u32, u32),
+    to: (u32, u32),
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// Ordered upgrade steps; each one takes a file from `from` to exactly `to`.
+const MIGRATIONS: &[Migration] = &[Migration {
+    id: "legacy-field-names",
+    from: (0, 0),
+    to: (1, 0),
+    apply: migrate_legacy_field_names,
+}];
+
+fn format_version(version: (u32, u32)) -> String {
+    format!("{}.{}", version.0, version.1)
+}
+
+fn parse_version(value: Option<&Value>) -> Result<(u32, u32), String> {
+    let text = match value {
+        None | Some(Value::Null) => return Ok((0, 0)),
+        Some(Value::String(text)) => text.trim().to_string(),
+        Some(Value::Number(number)) => number.to_string(),
+        Some(other) => return Err(format!("Unrecognized project file version: {}", other)),
+    };
+    let mut parts = text.splitn(2, '.');
+    let major = parts.next().and_then(|part| part.parse::<u32>().ok());
+    let minor = match parts.next() {
+        Some(part) => part.parse::<u32>().ok(),
+        None => Some(0),
+    };
+    match (major, minor) {
+        (Some(major), Some(minor)) => Ok((major, minor)),
+        _ => Err(format!("Unrecognized project file version: {}", text)),
+    }
+}
+
+/// Upgrades a project object in place to [`CURRENT_PROJECT_VERSION`] and
+/// returns the ids of the migrations that ran. Each run is also appended to the
+/// file's `migrationHistory`, so the record survives the next save.
+pub fn migrate_project(project: &mut Map<String, Value>) -> Result<Vec<String>, String> {
+    let mut version = parse_version(project.get("version"))?;
+    if version > CURRENT_PROJECT_VERSION {
+        return Err(format!(
+            "Project file version {} is newer than the supported version {}. Update AMV Notation to open it.",
+            format_version(version),
+            format_version(CURRENT_PROJECT_VERSION)
+        ));
+    }
+
+    let mut applied = Vec::new();
+    while version < CURRENT_PROJECT_VERSION {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|migration| migration.from == version)
+            .ok_or_else(|| {
+                format!(
+                    "No migration available from project version {}",
+                    format_version(version)
+                )
+            })?;
+
+        (migration.apply)(project);
+        record_migration(project, migration);
+        applied.push(migration.id.to_string());
+        version = migration.to;
+    }
+
+    project.insert(
+        "version".to_string(),
+        Value::String(format_version(CURRENT_PROJECT_VERSION)),
+    );
+    Ok(applied)
+}
+
+fn record_migration(project: &mut Map<String, Value>, migration: &Migration) {
+    let entry = serde_json::json!({
+        "id": migration.id,
+        "from": format_version(migration.from),
+        "to": format_version(migration.to),
+        "appliedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    match project.get_mut(MIGRATION_HISTORY_KEY) {
+        Some(Value::Array(history)) => history.push(entry),
+        _ => {
+            project.insert(MIGRATION_HISTORY_KEY.to_string(), Value::Array(vec![entry]));
+        }
+    }
+}
+
+fn snake_to_camel(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for ch in key.chars() {
+        if ch == '_' {
+            upper_next = !out.is_empty();
+        } else if upper_next {
+            out.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Renames the snake_case keys of one record (never of an id-keyed map) to
+/// camelCase. An existing camelCase key wins over its legacy spelling.
+fn camel_case_keys(value: &mut Value) {
+    let Value::Object(object) = value else {
+        return;
+    };
+    let legacy_keys: Vec<String> = object
+        .keys()
+        .filter(|key| key.contains('_'))
+        .cloned()
+        .collect();
+    for key in legacy_keys {
+        let camel = snake_to_camel(&key);
+        if camel == key {
+            continue;
+        }
+        if let Some(inner) = object.remove(&key) {
+            object.entry(camel).or_insert(inner);
+        }
+    }
+}
+
+/// Moves the first present legacy key to `target` unless `target` already exists.
+fn rename_first(object: &mut Map<String, Value>, target: &str, legacy: &[&str]) {
+    for key in legacy {
+        if let Some(value) = object.remove(*key) {
+            object.entry(target.to_string()).or_insert(value);
+        }
+    }
+}
+
+fn for_each_record<F: FnMut(&mut Value)>(value: Option<&mut Value>, mut op: F) {
+    match value {
+        Some(Value::Array(items)) => items.iter_mut().for_each(&mut op),
+        Some(Value::Object(map)) => map.values_mut().for_each(&mut op),
+        _ => {}
+    }
+}
+
+/// 0.0 -> 1.0: the first releases wrote snake_case keys and used a few field
+/// names that were renamed since.
+fn migrate_legacy_field_names(root: &mut Map<String, Value>) {
+    rename_first(root, "importedJudges", &["imported_judges"]);
+    rename_first(root, "baremeId", &["bareme_id"]);
+
+    if let Some(project) = root.get_mut("project") {
+        camel_case_keys(project);
+        if let Some(settings) = project.get_mut("settings") {
+            camel_case_keys(settings);
+            if let Value::Object(settings) = settings {
+                rename_first(settings, "contestCategoryPresets", &["categoryPresets"]);
+                rename_first(settings, "contestCategoryColors", &["categoryColors"]);
+                if let Some(Value::Bool(collab)) = settings.remove("showCollabMepLabels") {
+                    let mode = if collab { "collab_mep" } else { "all" };
+                    settings
+                        .entry("multiPseudoDisplayMode".to_string())
+                        .or_insert_with(|| Value::String(mode.to_string()));
+                }
+            }
+        }
+    }
+
+    for_each_record(root.get_mut("clips"), |clip| {
+        camel_case_keys(clip);
+        if let Value::Object(clip) = clip {
+            rename_first(clip, "contestCategory", &["category", "clipCategory"]);
+            rename_first(clip, "favorite", &["isFavorite"]);
+        }
+    });
+
+    for_each_record(root.get_mut("notes"), |note| {
+        camel_case_keys(note);
+        for_each_record(note.get_mut("scores"), camel_case_keys);
+    });
+
+    for_each_record(root.get_mut("importedJudges"), |judge| {
+        camel_case_keys(judge);
+        for_each_record(judge.get_mut("notes"), |note| {
+            camel_case_keys(note);
+            if let Value::Object(note) = note {
+                rename_first(note, "favorite", &["isFavorite"]);
+            }
+            for_each_record(note.get_mut("scores"), camel_case_keys);
+        });
+    });
+
+    let project_bareme = root
+        .get("project")
+        .and_then(|project| project.get("baremeId"))
+        .cloned();
+    if let Some(bareme_id) = project_bareme {
+        root.entry("baremeId".to_string()).or_insert(bareme_id);
+    }
+}
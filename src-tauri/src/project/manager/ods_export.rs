@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+use super::json_io;
+use super::results::ResultsModel;
+use super::results_workbook::{self, CellStyle, CellValue, Labels, ResultSheet, SheetCell};
+
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+const HEADER_COLOR: &str = "#1f2937";
+const EVEN_ROW_COLOR: &str = "#f3f4f6";
+const BORDER: &str = "0.06pt solid #d1d5db";
+/// Rough width of one Excel character unit at the default font.
+const CM_PER_CHARACTER: f64 = 0.19;
+const DEFAULT_COLUMN_WIDTH: f64 = 8.43;
+
+const NAMESPACES: &str = concat!(
+    r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" "#,
+    r#"xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" "#,
+    r#"xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" "#,
+    r#"xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" "#,
+    r#"xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" "#,
+    r#"xmlns:of="urn:oasis:names:tc:opendocument:xmlns:of:1.2" "#,
+    r#"xmlns:config="urn:oasis:names:tc:opendocument:xmlns:config:1.0" "#,
+    r#"xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" "#,
+    r#"office:version="1.3""#,
+);
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn is_cell_reference(token: &str) -> bool {
+    let token = token.replace('$', "");
+    let letters = token
+        .chars()
+        .take_while(|ch| ch.is_ascii_uppercase())
+        .count();
+    letters > 0 && letters < token.len() && token[letters..].chars().all(|ch| ch.is_ascii_digit())
+}
+
+/// `A1` or `A1:B2` as OpenFormula, optionally on a quoted sheet.
+fn open_reference(reference: &str, sheet: Option<&str>) -> String {
+    let sheet = sheet.unwrap_or("");
+    match reference.split_once(':') {
+        Some((first, last)) => format!("[{}.{}:.{}]", sheet, first, last),
+        None => format!("[{}.{}]", sheet, reference),
+    }
+}
+
+/// Rewrites the Excel A1 formulas of the layout in OpenFormula syntax:
+/// `SUM(B3:C3)` -> `SUM([.B3:.C3])`, `'Judge'!N5` -> `['Judge'.N5]` and `;`
+/// between arguments.
+fn open_formula(formula: &str) -> String {
+    let chars: Vec<char> = formula.trim_start_matches('=').chars().collect();
+    let mut out = String::from("of:=");
+    let mut index = 0;
+
+    // Reads a reference token, with its `:` range end if any.
+    let read_reference = |index: &mut usize| {
+        let start = *index;
+        let mut seen_colon = false;
+        while *index < chars.len() {
+            let ch = chars[*index];
+            if ch.is_ascii_alphanumeric() || ch == '$' || (ch == ':' && !seen_colon) {
+                seen_colon |= ch == ':';
+                *index += 1;
+            } else {
+                break;
+            }
+        }
+        chars[start..*index].iter().collect::<String>()
+    };
+
+    while index < chars.len() {
+        match chars[index] {
+            '"' => {
+                let start = index;
+                index += 1;
+                while index < chars.len() {
+                    if chars[index] == '"' {
+                        if chars.get(index + 1) == Some(&'"') {
+                            index += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    index += 1;
+                }
+                index = (index + 1).min(chars.len());
+                out.extend(&chars[start..index]);
+            }
+            '\'' => {
+                let start = index;
+                index += 1;
+                while index < chars.len() {
+                    if chars[index] == '\'' {
+                        if chars.get(index + 1) == Some(&'\'') {
+                            index += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    index += 1;
+                }
+                index = (index + 1).min(chars.len());
+                let sheet: String = chars[start..index].iter().collect();
+                if chars.get(index) == Some(&'!') {
+                    index += 1;
+                    let reference = read_reference(&mut index);
+                    out.push_str(&open_reference(&reference, Some(&sheet)));
+                } else {
+                    out.push_str(&sheet);
+                }
+            }
+            ',' => {
+                out.push(';');
+                index += 1;
+            }
+            ch if ch.is_ascii_alphabetic() || ch == '$' => {
+                let mut end = index;
+                let token = read_reference(&mut end);
+                let is_reference =
+                    chars.get(end) != Some(&'(') && token.split(':').all(is_cell_reference);
+                if is_reference {
+                    out.push_str(&open_reference(&token, None));
+                    index = end;
+                } else {
+                    // Function name: copy up to the parenthesis.
+                    while index < chars.len() && chars[index].is_ascii_alphanumeric() {
+                        out.push(chars[index]);
+                        index += 1;
+                    }
+                }
+            }
+            other => {
+                out.push(other);
+                index += 1;
+            }
+        }
+    }
+    out
+}
+
+fn style_properties(style: &CellStyle) -> String {
+    let header = |background: &str, color: &str, size: Option<&str>| {
+        format!(
+            concat!(
+                r#"<style:table-cell-properties fo:background-color="{}" fo:border="{}" "#,
+                r#"style:vertical-align="middle" fo:wrap-option="wrap"/>"#,
+                r#"<style:paragraph-properties fo:text-align="center"/>"#,
+                r#"<style:text-properties fo:font-weight="bold" fo:color="{}"{}/>"#,
+            ),
+            background,
+            BORDER,
+            color,
+            size.map(|size| format!(r#" fo:font-size="{}""#, size))
+                .unwrap_or_default()
+        )
+    };
+    let plain = |background: Option<&str>, bold: bool, centered: bool| {
+        format!(
+            r#"<style:table-cell-properties fo:border="{}"{}/>{}{}"#,
+            BORDER,
+            background
+                .map(|color| format!(r#" fo:background-color="{}""#, color))
+                .unwrap_or_default(),
+            if centered {
+                r#"<style:paragraph-properties fo:text-align="center"/>"#
+            } else {
+                ""
+            },
+            if bold {
+                r#"<style:text-properties fo:font-weight="bold"/>"#
+            } else {
+                ""
+            }
+        )
+    };
+    match style {
+        CellStyle::Title => header(HEADER_COLOR, "#ffffff", Some("13pt")),
+        CellStyle::Header => header(HEADER_COLOR, "#ffffff", None),
+        CellStyle::Category(color) => header(color, "#000000", None),
+        CellStyle::Name => plain(None, true, false),
+        CellStyle::Score { even } => plain(even.then_some(EVEN_ROW_COLOR), false, true),
+        CellStyle::Total => plain(None, true, true),
+    }
+}
+
+/// Cell and column styles shared by every sheet, named as they are met.
+#[derive(Default)]
+struct Styles {
+    cells: Vec<CellStyle>,
+    columns: Vec<f64>,
+}
+
+impl Styles {
+    fn cell(&mut self, style: &CellStyle) -> String {
+        let index = match self.cells.iter().position(|known| known == style) {
+            Some(index) => index,
+            None => {
+                self.cells.push(style.clone());
+                self.cells.len() - 1
+            }
+        };
+        format!("ce{}", index + 1)
+    }
+
+    fn column(&mut self, width: f64) -> String {
+        let index = match self.columns.iter().position(|known| *known == width) {
+            Some(index) => index,
+            None => {
+                self.columns.push(width);
+                self.columns.len() - 1
+            }
+        };
+        format!("co{}", index + 1)
+    }
+
+    fn xml(&self) -> String {
+        let mut out = String::from("<office:automatic-styles>");
+        for (index, width) in self.columns.iter().enumerate() {
+            let _ = write!(
+                out,
+                concat!(
+                    r#"<style:style style:name="co{}" style:family="table-column">"#,
+                    r#"<style:table-column-properties style:column-width="{:.2}cm"/>"#,
+                    "</style:style>"
+                ),
+                index + 1,
+                width * CM_PER_CHARACTER
+            );
+        }
+        for (index, style) in self.cells.iter().enumerate() {
+            let _ = write!(
+                out,
+                r#"<style:style style:name="ce{}" style:family="table-cell">{}</style:style>"#,
+                index + 1,
+                style_properties(style)
+            );
+        }
+        out.push_str("</office:automatic-styles>");
+        out
+    }
+}
+
+fn cell_xml(cell: &SheetCell, sheet: &ResultSheet, styles: &mut Styles) -> String {
+    let mut attributes = format!(r#" table:style-name="{}""#, styles.cell(&cell.style));
+    if let Some(merge) = sheet.merge_at(cell.row, cell.column) {
+        let _ = write!(
+            attributes,
+            r#" table:number-columns-spanned="{}" table:number-rows-spanned="{}""#,
+            merge.last_column - merge.first_column + 1,
+            merge.last_row - merge.first_row + 1
+        );
+    }
+    let (value, text) = match &cell.value {
+        CellValue::Text(text) => (
+            r#" office:value-type="string""#.to_string(),
+            Some(text.clone()),
+        ),
+        CellValue::Number(number) => (
+            format!(r#" office:value-type="float" office:value="{}""#, number),
+            Some(number.to_string()),
+        ),
+        CellValue::Blank => (String::new(), None),
+        CellValue::Formula { formula, cached } => {
+            let formula = format!(r#" table:formula="{}""#, escape(&open_formula(formula)));
+            match cached {
+                Some(number) => (
+                    format!(
+                        r#"{} office:value-type="float" office:value="{}""#,
+                        formula, number
+                    ),
+                    Some(number.to_string()),
+                ),
+                None => (
+                    format!(
+                        r#"{} office:value-type="string" office:string-value="""#,
+                        formula
+                    ),
+                    None,
+                ),
+            }
+        }
+    };
+    match text {
+        Some(text) => format!(
+            "<table:table-cell{}{}><text:p>{}</text:p></table:table-cell>",
+            attributes,
+            value,
+            escape(&text)
+        ),
+        None => format!("<table:table-cell{}{}/>", attributes, value),
+    }
+}
+
+fn table_xml(sheet: &ResultSheet, styles: &mut Styles) -> String {
+    let cells: HashMap<(u32, u16), &SheetCell> = sheet
+        .cells
+        .iter()
+        .map(|cell| ((cell.row, cell.column), cell))
+        .collect();
+    let last_row = sheet.cells.iter().map(|cell| cell.row).max().unwrap_or(0);
+    let last_column = sheet
+        .cells
+        .iter()
+        .map(|cell| cell.column)
+        .chain(sheet.merges.iter().map(|merge| merge.last_column))
+        .max()
+        .unwrap_or(0);
+    let widths: HashMap<u16, f64> = sheet.column_widths.iter().copied().collect();
+
+    let mut out = format!(r#"<table:table table:name="{}">"#, escape(&sheet.name));
+    for column in 0..=last_column {
+        let width = widths.get(&column).copied().unwrap_or(DEFAULT_COLUMN_WIDTH);
+        let _ = write!(
+            out,
+            r#"<table:table-column table:style-name="{}"/>"#,
+            styles.column(width)
+        );
+    }
+
+    for row in 0..=last_row {
+        out.push_str("<table:table-row>");
+        for column in 0..=last_column {
+            if sheet.is_covered(row, column) {
+                out.push_str("<table:covered-table-cell/>");
+            } else if let Some(cell) = cells.get(&(row, column)) {
+                out.push_str(&cell_xml(cell, sheet, styles));
+            } else {
+                out.push_str("<table:table-cell/>");
+            }
+        }
+        out.push_str("</table:table-row>");
+    }
+    out.push_str("</table:table>");
+    out
+}
+
+fn content_xml(sheets: &[ResultSheet]) -> String {
+    let mut styles = Styles::default();
+    let tables: String = sheets
+        .iter()
+        .map(|sheet| table_xml(sheet, &mut styles))
+        .collect();
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "<office:document-content {}>{}",
+            "<office:body><office:spreadsheet>{}</office:spreadsheet></office:body>",
+            "</office:document-content>"
+        ),
+        NAMESPACES,
+        styles.xml(),
+        tables
+    )
+}
+
+/// Frozen panes live in the view settings in ODS rather than on the table.
+fn settings_xml(sheets: &[ResultSheet]) -> String {
+    let item = |name: &str, kind: &str, value: u32| {
+        format!(
+            r#"<config:config-item config:name="{}" config:type="{}">{}</config:config-item>"#,
+            name, kind, value
+        )
+    };
+    let tables: String = sheets
+        .iter()
+        .filter_map(|sheet| {
+            let (row, column) = sheet.freeze?;
+            let column = u32::from(column);
+            Some(format!(
+                r#"<config:config-item-map-entry config:name="{}">{}</config:config-item-map-entry>"#,
+                escape(&sheet.name),
+                [
+                    item("HorizontalSplitMode", "short", 2),
+                    item("VerticalSplitMode", "short", 2),
+                    item("HorizontalSplitPosition", "int", column),
+                    item("VerticalSplitPosition", "int", row),
+                    item("ActiveSplitRange", "short", 2),
+                    item("PositionLeft", "int", 0),
+                    item("PositionRight", "int", column),
+                    item("PositionTop", "int", 0),
+                    item("PositionBottom", "int", row),
+                ]
+                .concat()
+            ))
+        })
+        .collect();
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "<office:document-settings {}><office:settings>",
+            r#"<config:config-item-set config:name="ooo:view-settings">"#,
+            r#"<config:config-item-map-indexed config:name="Views"><config:config-item-map-entry>"#,
+            r#"<config:config-item config:name="ViewId" config:type="string">view1</config:config-item>"#,
+            r#"<config:config-item-map-named config:name="Tables">{}</config:config-item-map-named>"#,
+            "</config:config-item-map-entry></config:config-item-map-indexed>",
+            "</config:config-item-set></office:settings></office:document-settings>"
+        ),
+        NAMESPACES, tables
+    )
+}
+
+fn manifest_xml() -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.3">"#,
+            r#"<manifest:file-entry manifest:full-path="/" manifest:version="1.3" manifest:media-type="{}"/>"#,
+            r#"<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>"#,
+            r#"<manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/>"#,
+            "</manifest:manifest>"
+        ),
+        MIMETYPE
+    )
+}
+
+/// Packs the sheets as an OpenDocument spreadsheet. The `mimetype` entry has
+/// to come first and uncompressed for the file to be recognised.
+pub fn build_spreadsheet(sheets: &[ResultSheet]) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let entries = [
+        ("mimetype", MIMETYPE.to_string(), stored),
+        ("content.xml", content_xml(sheets), deflated),
+        ("settings.xml", settings_xml(sheets), deflated),
+        ("META-INF/manifest.xml", manifest_xml(), deflated),
+    ];
+    for (name, content, options) in entries {
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(content.as_bytes()).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish spreadsheet: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+pub fn export_results_ods(
+    data: serde_json::Value,
+    file_path: &str,
+    labels: HashMap<String, String>,
+) -> Result<(), String> {
+    let model = ResultsModel::from_project(data)?;
+    let sheets = results_workbook::build_sheets(&model, &Labels(labels));
+    let bytes = build_spreadsheet(&sheets)?;
+    json_io::write_atomic(Path::new(file_path), &bytes, "export results spreadsheet")
+}
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 const PROJECTS_FOLDER_SETTING_KEY: &str = "projectsFolderPath";
 const BAREMES_FOLDER_SETTING_KEY: &str = "baremesFolderPath";
+const EXTRA_PROJECT_ROOTS_SETTING_KEY: &str = "projectRoots";
 
 pub fn ensure_directory_exists(path: &Path) -> Result<(), String> {
     fs::create_dir_all(path).map_err(|e| e.to_string())
@@ -24,10 +25,14 @@ pub fn projects_folder() -> Result<PathBuf, String> {
     Ok(folder)
 }
 
-fn configured_folder(setting_key: &str) -> Option<PathBuf> {
+fn read_settings() -> Option<serde_json::Value> {
     let settings_path = settings_file_path().ok()?;
     let content = fs::read_to_string(settings_path).ok()?;
-    let settings = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    serde_json::from_str::<serde_json::Value>(&content).ok()
+}
+
+fn configured_folder(setting_key: &str) -> Option<PathBuf> {
+    let settings = read_settings()?;
     let folder = settings
         .get(setting_key)
         .and_then(|value| value.as_str())?
@@ -44,6 +49,26 @@ pub fn configured_projects_folder() -> Option<PathBuf> {
     configured_folder(PROJECTS_FOLDER_SETTING_KEY)
 }
 
+/// The projects folder followed by the extra roots listed under `projectRoots`
+/// in the user settings, without duplicates.
+pub fn project_roots() -> Result<Vec<PathBuf>, String> {
+    let mut roots = vec![match configured_projects_folder() {
+        Some(folder) => folder,
+        None => projects_folder()?,
+    }];
+    let extra = read_settings()
+        .and_then(|settings| settings.get(EXTRA_PROJECT_ROOTS_SETTING_KEY).cloned())
+        .and_then(|value| value.as_array().cloned())
+        .unwrap_or_default();
+    for folder in extra.iter().filter_map(|value| value.as_str()) {
+        let folder = PathBuf::from(folder.trim());
+        if !folder.as_os_str().is_empty() && !roots.contains(&folder) {
+            roots.push(folder);
+        }
+    }
+    Ok(roots)
+}
+
 pub fn baremes_folder() -> Result<PathBuf, String> {
     let folder = match configured_folder(BAREMES_FOLDER_SETTING_KEY) {
         Some(folder) => folder,
@@ -1,17 +1,77 @@
 use std::path::Path;
 
+use super::audit_log;
+use super::bareme_revisions;
+use super::clip_links;
+use super::file_watch;
+use super::history;
 use super::json_io;
+use super::project_listing;
+use super::project_lock;
+use super::project_validation;
 
-pub fn save_project_file(data: serde_json::Value, file_path: String) -> Result<(), String> {
-    json_io::write_pretty_json(Path::new(&file_path), &data, "save project")
+fn judge_name(data: &serde_json::Value) -> &str {
+    project_listing::project_root(data)["project"]["judgeName"]
+        .as_str()
+        .unwrap_or("")
 }
 
-pub fn load_project_file(file_path: String) -> Result<serde_json::Value, String> {
+/// `reason` is recorded in the audit log with the score changes of this save.
+pub fn save_project_file(
+    data: serde_json::Value,
+    file_path: String,
+    reason: Option<&str>,
+) -> Result<(), String> {
+    let mut data = project_validation::normalize_project_value(data, "project")?;
+    bareme_revisions::pin_project(&mut data);
+    clip_links::record_fingerprints(&mut data);
     let path = Path::new(&file_path);
-    if !path.exists() {
+    project_lock::ensure_writable(path, judge_name(&data))?;
+    // Logged before the write: a change that reached the file is always in the log.
+    let appended = audit_log::record_changes(path, &mut data, reason)?;
+    if let Err(error) = json_io::write_pretty_json_with_backup(path, &data, "save project") {
+        appended.rollback();
+        return Err(error);
+    }
+    // The save itself succeeded; a failing snapshot must not be reported as a lost save.
+    if let Err(error) = history::record_snapshot(path, &data) {
+        eprintln!("[AMV] Failed to record project snapshot: {}", error);
+    }
+    Ok(())
+}
+
+/// Restores a history snapshot over the project file. The restore goes through
+/// the regular save path, so the state being replaced stays in the history.
+pub fn restore_snapshot(
+    file_path: String,
+    snapshot_id: String,
+) -> Result<serde_json::Value, String> {
+    let data = history::load_snapshot(&file_path, &snapshot_id)?;
+    let reason = format!("Restored snapshot {}", snapshot_id.trim());
+    save_project_file(data.clone(), file_path, Some(&reason))?;
+    Ok(data)
+}
+
+/// Loads a project and locks it for this instance. `take_over` replaces a lock
+/// left by another instance, typically a stale one after a crash.
+pub fn load_project_file(file_path: String, take_over: bool) -> Result<serde_json::Value, String> {
+    let path = Path::new(&file_path);
+    if !path.exists() && !json_io::backup_path(path).exists() {
         return Err(format!("Project file not found: {}", file_path));
     }
-    json_io::read_json(path, "read project", "parse project")
+    let data = json_io::read_json_with_backup(path, "read project", "parse project", |value| {
+        project_validation::normalize_project_value(value, "project file")
+    })?;
+    project_lock::acquire(path, judge_name(&data), take_over)?;
+    Ok(data)
+}
+
+/// Reads a file without locking it, for judge and barème imports that only pick
+/// data out of it. Files that are not valid projects are returned as they are.
+pub fn read_project_file(file_path: String) -> Result<serde_json::Value, String> {
+    let path = Path::new(&file_path);
+    let value = json_io::read_json(path, "read project", "parse project")?;
+    Ok(project_validation::normalize_project_value(value.clone(), "project file").unwrap_or(value))
 }
 
 pub fn export_json_file(data: serde_json::Value, file_path: String) -> Result<(), String> {
@@ -31,6 +91,22 @@ pub fn delete_project_file(file_path: String) -> Result<(), String> {
     if !path.is_file() {
         return Err(format!("Project path is not a file: {}", normalized));
     }
+    if let Some(lock) = project_lock::current_lock(path).filter(|lock| !lock.owned && !lock.stale) {
+        return Err(format!(
+            "Cannot delete a project opened elsewhere by {} on {}",
+            lock.judge_name, lock.host
+        ));
+    }
 
-    std::fs::remove_file(path).map_err(|error| format!("Failed to delete project: {}", error))
+    file_watch::record_removal(path);
+    std::fs::remove_file(path).map_err(|error| format!("Failed to delete project: {}", error))?;
+    let backup = json_io::backup_path(path);
+    if backup.is_file() {
+        let _ = std::fs::remove_file(backup);
+    }
+    let lock = project_lock::lock_path(path);
+    if lock.is_file() {
+        let _ = std::fs::remove_file(lock);
+    }
+    Ok(())
 }
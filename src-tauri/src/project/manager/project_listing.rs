@@ -1,28 +1,117 @@
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
+use walkdir::WalkDir;
+
+use super::baremes;
 use super::paths;
 use super::types::ProjectSummary;
 
+/// Some exports wrap the project under a `projectData` key.
+pub(super) fn project_root(data: &serde_json::Value) -> &serde_json::Value {
+    match data.get("projectData") {
+        Some(inner) if inner.is_object() => inner,
+        _ => data,
+    }
+}
+
+pub(super) fn project_updated_at(data: &serde_json::Value) -> String {
+    let project = &project_root(data)["project"];
+    project["updatedAt"]
+        .as_str()
+        .or_else(|| project["updated_at"].as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Returns `(clip count, scored clip count)`.
+pub(super) fn clip_counts(data: &serde_json::Value) -> (usize, usize) {
+    let Some(clips) = project_root(data)["clips"].as_array() else {
+        return (0, 0);
+    };
+    let scored = clips
+        .iter()
+        .filter(|clip| clip["scored"].as_bool().unwrap_or(false))
+        .count();
+    (clips.len(), scored)
+}
+
 fn parse_project_summary(data: &serde_json::Value, file_path: &Path) -> ProjectSummary {
-    let project = &data["project"];
+    let root = project_root(data);
+    let project = &root["project"];
     let name = project["name"].as_str().unwrap_or("Sans nom").to_string();
     let judge_name = project["judgeName"]
         .as_str()
         .or_else(|| project["judge_name"].as_str())
         .unwrap_or("")
         .to_string();
-    let updated_at = project["updatedAt"]
+    let updated_at = project_updated_at(data);
+    let (clip_count, scored_count) = clip_counts(data);
+    let bareme_id = root["baremeId"]
         .as_str()
-        .or_else(|| project["updated_at"].as_str())
+        .or_else(|| project["baremeId"].as_str())
+        .or_else(|| project["bareme_id"].as_str())
         .unwrap_or("")
         .to_string();
+    let bareme_name = [&root["bareme"], &data["bareme"]]
+        .into_iter()
+        .find(|bareme| bareme["id"].as_str() == Some(bareme_id.as_str()))
+        .and_then(|bareme| bareme["name"].as_str())
+        .unwrap_or("")
+        .to_string();
+    let imported_judge_count = root["importedJudges"]
+        .as_array()
+        .or_else(|| root["imported_judges"].as_array())
+        .map_or(0, |judges| judges.len());
 
     ProjectSummary {
         name,
         judge_name,
         updated_at,
         file_path: file_path.to_string_lossy().to_string(),
+        clip_count,
+        scored_count,
+        completion_percent: completion_percent(clip_count, scored_count),
+        bareme_id,
+        bareme_name,
+        imported_judge_count,
+        size_bytes: 0,
+        contest: String::new(),
+        parse_error: None,
+    }
+}
+
+fn completion_percent(clip_count: usize, scored_count: usize) -> f64 {
+    if clip_count == 0 {
+        return 0.0;
+    }
+    (scored_count as f64 * 1000.0 / clip_count as f64).round() / 10.0
+}
+
+/// Summary for a file that could not be read or parsed, so the welcome screen
+/// can flag it instead of hiding it.
+fn broken_project_summary(file_path: &Path, error: String) -> ProjectSummary {
+    ProjectSummary {
+        name: file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        judge_name: String::new(),
+        updated_at: String::new(),
+        file_path: file_path.to_string_lossy().to_string(),
+        clip_count: 0,
+        scored_count: 0,
+        completion_percent: 0.0,
+        bareme_id: String::new(),
+        bareme_name: String::new(),
+        imported_judge_count: 0,
+        size_bytes: 0,
+        contest: String::new(),
+        parse_error: Some(error),
     }
 }
 
@@ -39,28 +128,188 @@ pub fn default_projects_folder() -> Result<String, String> {
     Ok(folder.to_string_lossy().to_string())
 }
 
-pub fn list_projects(folder_path: String) -> Result<Vec<ProjectSummary>, String> {
-    let path = Path::new(&folder_path);
-    if !path.exists() {
-        return Ok(Vec::new());
+/// Summaries keyed by path and invalidated by mtime and size, so reopening the
+/// welcome screen on a folder of hundreds of projects only re-parses the files
+/// that changed.
+struct CachedSummary {
+    modified: Option<SystemTime>,
+    size: u64,
+    project: ListedProject,
+}
+
+/// A listed project plus the clip names the search filters on, which are not
+/// part of the summary sent to the welcome screen.
+#[derive(Clone)]
+pub(super) struct ListedProject {
+    pub summary: ProjectSummary,
+    pub clip_names: Vec<String>,
+}
+
+lazy_static! {
+    static ref SUMMARY_CACHE: Mutex<HashMap<PathBuf, CachedSummary>> = Mutex::new(HashMap::new());
+}
+
+fn clip_names(data: &serde_json::Value) -> Vec<String> {
+    let Some(clips) = project_root(data)["clips"].as_array() else {
+        return Vec::new();
+    };
+    clips
+        .iter()
+        .flat_map(|clip| {
+            ["displayName", "fileName", "author"]
+                .into_iter()
+                .filter_map(|key| clip[key].as_str())
+                .filter(|name| !name.trim().is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn read_project(file_path: &Path) -> ListedProject {
+    let content = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(error) => {
+            return ListedProject {
+                summary: broken_project_summary(file_path, error.to_string()),
+                clip_names: Vec::new(),
+            }
+        }
+    };
+    match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(data) => ListedProject {
+            summary: parse_project_summary(&data, file_path),
+            clip_names: clip_names(&data),
+        },
+        Err(error) => ListedProject {
+            summary: broken_project_summary(file_path, error.to_string()),
+            clip_names: Vec::new(),
+        },
+    }
+}
+
+fn cached_project(file_path: &Path, metadata: &fs::Metadata) -> ListedProject {
+    let modified = metadata.modified().ok();
+    let size = metadata.len();
+    let mut cache = SUMMARY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.get(file_path) {
+        if cached.modified == modified && cached.size == size {
+            return cached.project.clone();
+        }
+    }
+
+    let mut project = read_project(file_path);
+    project.summary.size_bytes = size;
+    cache.insert(
+        file_path.to_path_buf(),
+        CachedSummary {
+            modified,
+            size,
+            project: project.clone(),
+        },
+    );
+    project
+}
+
+/// Drops cached summaries of files that no longer exist within `max_depth`
+/// levels of `folder`.
+fn prune_summary_cache(folder: &Path, max_depth: usize, seen: &HashSet<PathBuf>) {
+    let mut cache = SUMMARY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|path, _| {
+        let within = path
+            .strip_prefix(folder)
+            .is_ok_and(|relative| relative.components().count() <= max_depth);
+        !within || seen.contains(path)
+    });
+}
+
+/// Fills barème names the project files do not embed from the barèmes folder.
+fn resolve_bareme_names<'a, I>(projects: I)
+where
+    I: IntoIterator<Item = &'a mut ProjectSummary>,
+{
+    let mut names = None;
+    for project in projects {
+        if project.bareme_name.is_empty() && !project.bareme_id.is_empty() {
+            let names = names.get_or_insert_with(baremes::bareme_names);
+            if let Some(name) = names.get(&project.bareme_id) {
+                project.bareme_name = name.clone();
+            }
+        }
     }
+}
 
+/// First folder under `root` holding the file, which is how teams sort
+/// projects per contest (`Japan Expo 2025/Lightning.json`).
+fn contest_folder(root: &Path, file_path: &Path) -> String {
+    let relative = file_path.strip_prefix(root).unwrap_or(file_path);
+    let mut components = relative.components();
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => first.as_os_str().to_string_lossy().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Walks `root` up to `max_depth` levels (1 = the folder itself), skipping
+/// hidden folders such as `.history` and the barèmes folder.
+pub(super) fn collect_projects(root: &Path, max_depth: usize) -> Vec<ListedProject> {
+    if !root.is_dir() {
+        return Vec::new();
+    }
+    let baremes_folder = paths::baremes_folder().ok();
     let mut projects = Vec::new();
+    let mut seen = HashSet::new();
 
-    let entries = fs::read_dir(path).map_err(|e| e.to_string())?;
-    for entry in entries.flatten() {
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth.max(1))
+        .into_iter()
+        .filter_entry(|entry| {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            let is_baremes =
+                entry.file_type().is_dir() && baremes_folder.as_deref() == Some(entry.path());
+            !hidden && !is_baremes
+        });
+    for entry in walker.flatten() {
         let file_path = entry.path();
-        if !is_json_file(file_path.as_path()) {
+        if !entry.file_type().is_file() || !is_json_file(file_path) {
             continue;
         }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
 
-        if let Ok(content) = fs::read_to_string(&file_path) {
-            if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                projects.push(parse_project_summary(&data, &file_path));
-            }
-        }
+        let mut project = cached_project(file_path, &metadata);
+        project.summary.contest = contest_folder(root, file_path);
+        projects.push(project);
+        seen.insert(file_path.to_path_buf());
+    }
+    prune_summary_cache(root, max_depth.max(1), &seen);
+    resolve_bareme_names(projects.iter_mut().map(|project| &mut project.summary));
+
+    projects
+}
+
+pub fn list_projects(folder_path: String) -> Result<Vec<ProjectSummary>, String> {
+    list_projects_recursive(folder_path, 1)
+}
+
+pub fn list_projects_recursive(
+    folder_path: String,
+    max_depth: usize,
+) -> Result<Vec<ProjectSummary>, String> {
+    let path = Path::new(&folder_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    if !path.is_dir() {
+        return Err(format!("{} is not a folder", folder_path));
     }
 
+    let mut projects: Vec<ProjectSummary> = collect_projects(path, max_depth)
+        .into_iter()
+        .map(|project| project.summary)
+        .collect();
     projects.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     Ok(projects)
 }
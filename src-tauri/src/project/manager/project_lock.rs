@@ -0,0 +1,246 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::json_io;
+use super::types::ProjectLockInfo;
+
+/// A lock whose holder has not saved for this long is reported as stale even
+/// when its process cannot be checked (other machine on a shared drive).
+const STALE_AFTER_HOURS: i64 = 12;
+
+/// Content of `<project>.json.lock`, written next to the project so that every
+/// machine opening it from a shared drive sees the same file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockHolder {
+    #[serde(default)]
+    judge_name: String,
+    #[serde(default)]
+    host: String,
+    #[serde(default)]
+    pid: u32,
+    #[serde(default)]
+    instance_id: String,
+    #[serde(default)]
+    acquired_at: String,
+    #[serde(default)]
+    refreshed_at: String,
+}
+
+lazy_static! {
+    /// Tells this app instance apart from another one started with the same PID
+    /// on another machine.
+    static ref INSTANCE_ID: String = uuid::Uuid::new_v4().to_string();
+    static ref HELD_LOCKS: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+fn host_name() -> String {
+    gethostname::gethostname().to_string_lossy().to_string()
+}
+
+fn read_holder(lock: &Path) -> Option<LockHolder> {
+    let content = fs::read_to_string(lock).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn is_own(holder: &LockHolder) -> bool {
+    holder.instance_id == *INSTANCE_ID
+}
+
+#[cfg(target_os = "windows")]
+fn process_is_running(pid: u32) -> bool {
+    // kernel32 is auto-linked on the MSVC target.
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> isize;
+        fn GetExitCodeProcess(process: isize, code: *mut u32) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+    const ERROR_INVALID_PARAMETER: i32 = 87;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle == 0 {
+            // Access denied still means the process exists.
+            return std::io::Error::last_os_error().raw_os_error() != Some(ERROR_INVALID_PARAMETER);
+        }
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(handle, &mut code);
+        CloseHandle(handle);
+        ok == 0 || code == STILL_ACTIVE
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn process_is_running(pid: u32) -> bool {
+    // Signal 0 only checks that the process exists.
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    const EPERM: i32 = 1;
+    if pid == 0 || pid > i32::MAX as u32 {
+        return false;
+    }
+    unsafe {
+        kill(pid as i32, 0) == 0 || std::io::Error::last_os_error().raw_os_error() == Some(EPERM)
+    }
+}
+
+/// Stale when the holder process is gone (checked on this machine only) or has
+/// not refreshed the lock for [`STALE_AFTER_HOURS`].
+fn is_stale(holder: &LockHolder) -> bool {
+    if holder.host == host_name() && !process_is_running(holder.pid) {
+        return true;
+    }
+    match chrono::DateTime::parse_from_rfc3339(&holder.refreshed_at) {
+        Ok(refreshed) => {
+            chrono::Utc::now().signed_duration_since(refreshed)
+                > chrono::Duration::hours(STALE_AFTER_HOURS)
+        }
+        Err(_) => true,
+    }
+}
+
+fn lock_info(holder: &LockHolder) -> ProjectLockInfo {
+    ProjectLockInfo {
+        judge_name: holder.judge_name.clone(),
+        host: holder.host.clone(),
+        pid: holder.pid,
+        acquired_at: holder.acquired_at.clone(),
+        refreshed_at: holder.refreshed_at.clone(),
+        owned: is_own(holder),
+        stale: !is_own(holder) && is_stale(holder),
+    }
+}
+
+fn locked_error(holder: &LockHolder) -> String {
+    let who = if holder.judge_name.trim().is_empty() {
+        "another judge".to_string()
+    } else {
+        holder.judge_name.trim().to_string()
+    };
+    let mut message = format!(
+        "Project opened elsewhere by {} on {} (PID {}) since {}.",
+        who, holder.host, holder.pid, holder.acquired_at
+    );
+    if is_stale(holder) {
+        message.push_str(" The lock looks stale; take it over to continue.");
+    } else {
+        message.push_str(" Close it there first.");
+    }
+    message
+}
+
+fn write_holder(lock: &Path, holder: &LockHolder, create_new: bool) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(holder).map_err(|e| e.to_string())?;
+    if create_new {
+        // `create_new` fails if another instance created the lock in between.
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(lock)
+            .map_err(|e| format!("Failed to create project lock: {}", e))?;
+        file.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write project lock: {}", e))
+    } else {
+        json_io::write_atomic(lock, json.as_bytes(), "write project lock")
+    }
+}
+
+fn new_holder(judge_name: &str, acquired_at: Option<String>) -> LockHolder {
+    let now = chrono::Utc::now().to_rfc3339();
+    LockHolder {
+        judge_name: judge_name.trim().to_string(),
+        host: host_name(),
+        pid: std::process::id(),
+        instance_id: INSTANCE_ID.clone(),
+        acquired_at: acquired_at.unwrap_or_else(|| now.clone()),
+        refreshed_at: now,
+    }
+}
+
+/// Takes the lock of `path` for this instance, or refreshes it when this
+/// instance already holds it. A lock held elsewhere is only replaced when
+/// `take_over` is set and the lock is stale; a live holder is never evicted.
+pub fn acquire(path: &Path, judge_name: &str, take_over: bool) -> Result<(), String> {
+    let lock = lock_path(path);
+    let (holder, create_new) = match read_holder(&lock) {
+        None if !lock.exists() => (new_holder(judge_name, None), true),
+        None => (new_holder(judge_name, None), false),
+        Some(current) if is_own(&current) => {
+            (new_holder(judge_name, Some(current.acquired_at)), false)
+        }
+        Some(current) if take_over && is_stale(&current) => {
+            eprintln!(
+                "[AMV] Taking over stale project lock of {} held by {} on {}",
+                path.display(),
+                current.judge_name,
+                current.host
+            );
+            (new_holder(judge_name, None), false)
+        }
+        Some(current) => return Err(locked_error(&current)),
+    };
+
+    if let Err(error) = write_holder(&lock, &holder, create_new) {
+        // Lost a creation race: report whoever won it.
+        if let Some(current) = read_holder(&lock).filter(|current| !is_own(current)) {
+            return Err(locked_error(&current));
+        }
+        return Err(error);
+    }
+    HELD_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(lock);
+    Ok(())
+}
+
+/// Refuses a save while another instance holds the lock; otherwise the
+/// lock is (re)taken so the heartbeat stays fresh.
+pub fn ensure_writable(path: &Path, judge_name: &str) -> Result<(), String> {
+    acquire(path, judge_name, false)
+}
+
+/// Removes the lock if this instance holds it; locks held elsewhere are kept.
+pub fn release(path: &Path) -> Result<(), String> {
+    let lock = lock_path(path);
+    HELD_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&lock);
+    match read_holder(&lock) {
+        Some(holder) if !is_own(&holder) => Ok(()),
+        _ if !lock.exists() => Ok(()),
+        _ => fs::remove_file(&lock).map_err(|e| format!("Failed to release project lock: {}", e)),
+    }
+}
+
+/// Releases every lock this instance took, on app exit.
+pub fn release_all() {
+    let held: Vec<PathBuf> = HELD_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .collect();
+    for lock in held {
+        if read_holder(&lock).is_some_and(|holder| is_own(&holder)) {
+            let _ = fs::remove_file(&lock);
+        }
+    }
+}
+
+pub fn current_lock(path: &Path) -> Option<ProjectLockInfo> {
+    read_holder(&lock_path(path)).map(|holder| lock_info(&holder))
+}
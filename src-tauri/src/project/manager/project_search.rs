@@ -0,0 +1,161 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use super::paths;
+use super::project_listing::{self, ListedProject};
+use super::types::{ProjectSearchGroup, ProjectSearchQuery, ProjectSummary};
+
+/// Contest folders are one level deep, projects sometimes sit in a
+/// `Contest/Category/` sub-folder; deeper trees are usually video folders.
+const DEFAULT_SEARCH_DEPTH: usize = 3;
+const MAX_SEARCH_DEPTH: usize = 8;
+
+/// Lowercases, collapses whitespace and strips the accents judges type
+/// inconsistently ("Barème" / "bareme").
+pub(super) fn fold(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .chars()
+        .map(|ch| match ch {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            other => other,
+        })
+        .collect()
+}
+
+/// `None` when the filter is unset or blank, the folded needle otherwise.
+fn needle(filter: &Option<String>) -> Option<String> {
+    filter
+        .as_deref()
+        .map(fold)
+        .filter(|needle| !needle.is_empty())
+}
+
+fn date_bound(bound: &Option<String>) -> Option<String> {
+    bound
+        .as_deref()
+        .map(str::trim)
+        .filter(|bound| !bound.is_empty())
+        .map(|bound| bound.chars().take(10).collect())
+}
+
+struct Filters {
+    contest: Option<String>,
+    judge_name: Option<String>,
+    bareme: Option<String>,
+    clip_name: Option<String>,
+    updated_from: Option<String>,
+    updated_to: Option<String>,
+}
+
+impl Filters {
+    fn new(query: &ProjectSearchQuery) -> Self {
+        Filters {
+            contest: needle(&query.contest),
+            judge_name: needle(&query.judge_name),
+            bareme: needle(&query.bareme),
+            clip_name: needle(&query.clip_name),
+            updated_from: date_bound(&query.updated_from),
+            updated_to: date_bound(&query.updated_to),
+        }
+    }
+
+    fn matches(&self, project: &ListedProject) -> bool {
+        let summary = &project.summary;
+        let contains = |haystack: &str, needle: &str| fold(haystack).contains(needle);
+
+        if let Some(contest) = &self.contest {
+            if !contains(&summary.contest, contest) && !contains(&summary.name, contest) {
+                return false;
+            }
+        }
+        if let Some(judge) = &self.judge_name {
+            if !contains(&summary.judge_name, judge) {
+                return false;
+            }
+        }
+        if let Some(bareme) = &self.bareme {
+            if !contains(&summary.bareme_id, bareme) && !contains(&summary.bareme_name, bareme) {
+                return false;
+            }
+        }
+        if let Some(clip) = &self.clip_name {
+            if !project.clip_names.iter().any(|name| contains(name, clip)) {
+                return false;
+            }
+        }
+
+        if self.updated_from.is_some() || self.updated_to.is_some() {
+            let day: String = summary.updated_at.chars().take(10).collect();
+            if day.is_empty() {
+                return false;
+            }
+            if self.updated_from.as_ref().is_some_and(|from| day < *from) {
+                return false;
+            }
+            if self.updated_to.as_ref().is_some_and(|to| day > *to) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn search_roots(query: &ProjectSearchQuery) -> Result<Vec<PathBuf>, String> {
+    match &query.roots {
+        Some(roots) if !roots.is_empty() => Ok(roots
+            .iter()
+            .map(|root| root.trim())
+            .filter(|root| !root.is_empty())
+            .map(PathBuf::from)
+            .collect()),
+        _ => paths::project_roots(),
+    }
+}
+
+/// Searches every project root and groups the matches by contest folder.
+/// Contests are sorted by name with top-level projects last; projects inside a
+/// contest are sorted by last update, newest first.
+pub fn search_projects(query: ProjectSearchQuery) -> Result<Vec<ProjectSearchGroup>, String> {
+    let depth = query
+        .max_depth
+        .unwrap_or(DEFAULT_SEARCH_DEPTH)
+        .clamp(1, MAX_SEARCH_DEPTH);
+    let filters = Filters::new(&query);
+
+    let mut seen = HashSet::new();
+    let mut groups: BTreeMap<(bool, String), Vec<ProjectSummary>> = BTreeMap::new();
+    for root in search_roots(&query)? {
+        for project in project_listing::collect_projects(&root, depth) {
+            if !filters.matches(&project) || !seen.insert(project.summary.file_path.clone()) {
+                continue;
+            }
+            let contest = project.summary.contest.clone();
+            groups
+                .entry((contest.is_empty(), fold(&contest)))
+                .or_default()
+                .push(project.summary);
+        }
+    }
+
+    Ok(groups
+        .into_values()
+        .map(|mut projects| {
+            projects.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+            ProjectSearchGroup {
+                contest: projects[0].contest.clone(),
+                projects,
+            }
+        })
+        .collect())
+}
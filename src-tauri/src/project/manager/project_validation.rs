@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use super::migrations;
+use super::project_listing;
+use super::types::{ProjectData, ProjectValidationIssue};
+
+const MAX_REPORTED_ISSUES: usize = 8;
+const MULTI_PSEUDO_DISPLAY_MODES: &[&str] = &["collab_mep", "first_three", "all"];
+const CLIP_NAME_PATTERNS: &[&str] = &["pseudo_clip", "clip_pseudo"];
+
+fn issue(path: impl Into<String>, message: impl Into<String>) -> ProjectValidationIssue {
+    ProjectValidationIssue {
+        path: path.into(),
+        message: message.into(),
+    }
+}
+
+/// Deserializes a raw project through the typed model. Type errors are reported
+/// with the JSON path of the offending value.
+pub fn parse_project(value: serde_json::Value) -> Result<ProjectData, Vec<ProjectValidationIssue>> {
+    let mut data: ProjectData = serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = error.path().to_string();
+        vec![issue(
+            if path == "." { String::new() } else { path },
+            error.into_inner().to_string(),
+        )]
+    })?;
+
+    normalize_project(&mut data);
+    let issues = validate_project(&data);
+    if issues.is_empty() {
+        Ok(data)
+    } else {
+        Err(issues)
+    }
+}
+
+/// Fills identifiers the frontend leaves implicit (a note's `clipId` is its map
+/// key, the top-level `baremeId` mirrors `project.baremeId`).
+fn normalize_project(data: &mut ProjectData) {
+    if data.bareme_id.is_empty() {
+        data.bareme_id = data.project.bareme_id.clone();
+    } else if data.project.bareme_id.is_empty() {
+        data.project.bareme_id = data.bareme_id.clone();
+    }
+
+    for (clip_id, note) in data.notes.iter_mut() {
+        if note.clip_id.is_empty() {
+            note.clip_id = clip_id.clone();
+        }
+        for (criterion_id, score) in note.scores.iter_mut() {
+            if score.criterion_id.is_empty() {
+                score.criterion_id = criterion_id.clone();
+            }
+        }
+    }
+
+    for judge in data.imported_judges.iter_mut() {
+        judge.judge_name = judge.judge_name.trim().to_string();
+    }
+}
+
+pub fn validate_project(data: &ProjectData) -> Vec<ProjectValidationIssue> {
+    let mut issues = Vec::new();
+
+    if data.project.id.trim().is_empty() {
+        issues.push(issue("project.id", "project id is missing"));
+    }
+
+    let settings = &data.project.settings;
+    if settings.auto_save_interval <= 0.0 {
+        issues.push(issue(
+            "project.settings.autoSaveInterval",
+            "must be greater than 0",
+        ));
+    }
+    if settings.default_playback_speed <= 0.0 {
+        issues.push(issue(
+            "project.settings.defaultPlaybackSpeed",
+            "must be greater than 0",
+        ));
+    }
+    if !(0.0..=100.0).contains(&settings.default_volume) {
+        issues.push(issue(
+            "project.settings.defaultVolume",
+            "must be between 0 and 100",
+        ));
+    }
+    if !MULTI_PSEUDO_DISPLAY_MODES.contains(&settings.multi_pseudo_display_mode.as_str()) {
+        issues.push(issue(
+            "project.settings.multiPseudoDisplayMode",
+            format!("unknown mode \"{}\"", settings.multi_pseudo_display_mode),
+        ));
+    }
+    if !CLIP_NAME_PATTERNS.contains(&settings.clip_name_pattern.as_str()) {
+        issues.push(issue(
+            "project.settings.clipNamePattern",
+            format!("unknown pattern \"{}\"", settings.clip_name_pattern),
+        ));
+    }
+
+    let mut clip_ids = HashSet::new();
+    for (index, clip) in data.clips.iter().enumerate() {
+        if clip.id.trim().is_empty() {
+            issues.push(issue(format!("clips[{}].id", index), "clip id is missing"));
+        } else if !clip_ids.insert(clip.id.as_str()) {
+            issues.push(issue(
+                format!("clips[{}].id", index),
+                format!("duplicate clip id \"{}\"", clip.id),
+            ));
+        }
+        if clip.duration < 0.0 {
+            issues.push(issue(
+                format!("clips[{}].duration", index),
+                "must not be negative",
+            ));
+        }
+        if clip.thumbnail_time.is_some_and(|time| time < 0.0) {
+            issues.push(issue(
+                format!("clips[{}].thumbnailTime", index),
+                "must not be negative",
+            ));
+        }
+    }
+
+    for (clip_id, note) in &data.notes {
+        if note.clip_id != *clip_id {
+            issues.push(issue(
+                format!("notes.{}.clipId", clip_id),
+                format!("does not match its key (\"{}\")", note.clip_id),
+            ));
+        }
+        for (criterion_id, score) in &note.scores {
+            if score.criterion_id != *criterion_id {
+                issues.push(issue(
+                    format!("notes.{}.scores.{}.criterionId", clip_id, criterion_id),
+                    format!("does not match its key (\"{}\")", score.criterion_id),
+                ));
+            }
+        }
+    }
+
+    let mut judge_names = HashSet::new();
+    for (index, judge) in data.imported_judges.iter().enumerate() {
+        if judge.judge_name.is_empty() {
+            issues.push(issue(
+                format!("importedJudges[{}].judgeName", index),
+                "judge name is missing",
+            ));
+        } else if !judge_names.insert(judge.judge_name.to_lowercase()) {
+            issues.push(issue(
+                format!("importedJudges[{}].judgeName", index),
+                format!("duplicate judge \"{}\"", judge.judge_name),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Flattens validation issues into the single error string returned by commands.
+pub fn format_issues(context: &str, issues: &[ProjectValidationIssue]) -> String {
+    let mut parts: Vec<String> = issues
+        .iter()
+        .take(MAX_REPORTED_ISSUES)
+        .map(|issue| {
+            if issue.path.is_empty() {
+                issue.message.clone()
+            } else {
+                format!("{}: {}", issue.path, issue.message)
+            }
+        })
+        .collect();
+    if issues.len() > MAX_REPORTED_ISSUES {
+        parts.push(format!("and {} more", issues.len() - MAX_REPORTED_ISSUES));
+    }
+    format!("Invalid {}: {}", context, parts.join("; "))
+}
+
+/// Validates a raw project value and returns its normalized form. Wrapped
+/// exports keep their wrapper around the normalized project.
+pub fn normalize_project_value(
+    value: serde_json::Value,
+    context: &str,
+) -> Result<serde_json::Value, String> {
+    match value {
+        serde_json::Value::Object(mut root)
+            if root
+                .get("projectData")
+                .is_some_and(|inner| inner.is_object()) =>
+        {
+            let inner = root.remove("projectData").unwrap_or_default();
+            let normalized = normalize_project_value(inner, context)?;
+            root.insert("projectData".to_string(), normalized);
+            Ok(serde_json::Value::Object(root))
+        }
+        mut value => {
+            if let serde_json::Value::Object(project) = &mut value {
+                let applied = migrations::migrate_project(project)?;
+                if !applied.is_empty() {
+                    eprintln!("[AMV] Migrated {}: {}", context, applied.join(", "));
+                }
+            }
+            let data = parse_project(value).map_err(|issues| format_issues(context, &issues))?;
+            serde_json::to_value(data).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Issue list for the `validate_project` command; an empty list means valid.
+pub fn project_issues(value: &serde_json::Value) -> Vec<ProjectValidationIssue> {
+    let mut payload = project_listing::project_root(value).clone();
+    if let serde_json::Value::Object(project) = &mut payload {
+        if let Err(message) = migrations::migrate_project(project) {
+            return vec![issue("version", message)];
+        }
+    }
+    parse_project(payload).err().unwrap_or_default()
+}
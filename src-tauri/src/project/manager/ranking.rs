@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+
+use super::aggregation;
+use super::project_search::fold;
+use super::results::ResultsModel;
+use super::types::{
+    AggregatedClip, AggregatedResults, AggregationMethod, RankedClip, RankingResult, TieBreaker,
+    TieResolution,
+};
+
+/// Scores are rounded to hundredths; closer than this they are equal.
+const EPSILON: f64 = 1e-6;
+
+/// Better values first, missing values last.
+fn compare(a: Option<f64>, b: Option<f64>, higher_is_better: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if (a - b).abs() < EPSILON => Ordering::Equal,
+        (Some(a), Some(b)) if higher_is_better => b.total_cmp(&a),
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Each tied clip's value under the rule, and whether higher wins.
+fn tie_breaker_values(
+    tie_breaker: &TieBreaker,
+    group: &[&AggregatedClip],
+    results: &AggregatedResults,
+) -> (Vec<Option<f64>>, bool) {
+    match tie_breaker {
+        TieBreaker::CategoryScore { category } => {
+            let wanted = fold(category);
+            let values = group
+                .iter()
+                .map(|clip| {
+                    clip.categories
+                        .iter()
+                        .find(|score| fold(&score.category) == wanted)
+                        .and_then(|score| score.score)
+                })
+                .collect();
+            (values, results.higher_is_better)
+        }
+        TieBreaker::FirstPlaceVotes => {
+            let mut votes = vec![0.0; group.len()];
+            for judge in 0..results.judges.len() {
+                let points: Vec<Option<f64>> =
+                    group.iter().map(|clip| clip.judges[judge].points).collect();
+                let Some(best) = points.iter().flatten().copied().reduce(f64::max) else {
+                    continue;
+                };
+                // A judge with several tied favourites votes for each of them.
+                for (index, value) in points.iter().enumerate() {
+                    if value.is_some_and(|value| (value - best).abs() < EPSILON) {
+                        votes[index] += 1.0;
+                    }
+                }
+            }
+            (votes.into_iter().map(Some).collect(), true)
+        }
+        TieBreaker::LowerSpread => {
+            let values = group
+                .iter()
+                .map(|clip| {
+                    let points: Vec<f64> = clip
+                        .judges
+                        .iter()
+                        .filter_map(|judge| judge.points)
+                        .collect();
+                    aggregation::spread(&points).map(|(_, std_dev)| std_dev)
+                })
+                .collect();
+            (values, false)
+        }
+        TieBreaker::OrganizerDecision { clip_ids } => {
+            let values = group
+                .iter()
+                .map(|clip| {
+                    clip_ids
+                        .iter()
+                        .position(|id| *id == clip.clip_id)
+                        .map(|position| position as f64)
+                })
+                .collect();
+            (values, false)
+        }
+    }
+}
+
+/// Splits a group of clips tied at `rank` with the first rule that tells any
+/// of them apart, then the remaining rules on what is still tied. Returns the
+/// group in its final order as runs of clips that stay tied.
+fn split_tie<'a>(
+    group: Vec<&'a AggregatedClip>,
+    tie_breakers: &[TieBreaker],
+    rank: u32,
+    results: &AggregatedResults,
+    ties: &mut Vec<TieResolution>,
+) -> Vec<Vec<&'a AggregatedClip>> {
+    let score = group[0].score;
+    for (index, tie_breaker) in tie_breakers.iter().enumerate() {
+        let (values, higher_is_better) = tie_breaker_values(tie_breaker, &group, results);
+        let mut ordered: Vec<(&AggregatedClip, Option<f64>)> =
+            group.iter().copied().zip(values).collect();
+        ordered.sort_by(|a, b| compare(a.1, b.1, higher_is_better));
+
+        let mut runs: Vec<Vec<(&AggregatedClip, Option<f64>)>> = Vec::new();
+        for entry in ordered {
+            match runs.last_mut() {
+                Some(run) if compare(run[0].1, entry.1, higher_is_better) == Ordering::Equal => {
+                    run.push(entry)
+                }
+                _ => runs.push(vec![entry]),
+            }
+        }
+        if runs.len() < 2 {
+            continue;
+        }
+
+        ties.push(TieResolution {
+            rank,
+            score,
+            clip_ids: runs
+                .iter()
+                .flatten()
+                .map(|(clip, _)| clip.clip_id.clone())
+                .collect(),
+            tie_breaker: Some(tie_breaker.clone()),
+            values: runs.iter().flatten().map(|(_, value)| *value).collect(),
+            resolved: runs.iter().all(|run| run.len() == 1),
+        });
+
+        let mut out = Vec::new();
+        let mut run_rank = rank;
+        for run in runs {
+            let size = run.len() as u32;
+            let clips: Vec<&AggregatedClip> = run.into_iter().map(|(clip, _)| clip).collect();
+            if clips.len() == 1 {
+                out.push(clips);
+            } else {
+                out.extend(split_tie(
+                    clips,
+                    &tie_breakers[index + 1..],
+                    run_rank,
+                    results,
+                    ties,
+                ));
+            }
+            run_rank += size;
+        }
+        return out;
+    }
+
+    ties.push(TieResolution {
+        rank,
+        score,
+        clip_ids: group.iter().map(|clip| clip.clip_id.clone()).collect(),
+        tie_breaker: None,
+        values: vec![None; group.len()],
+        resolved: false,
+    });
+    vec![group]
+}
+
+/// Ranks the aggregated clips, optionally within one contest category, and
+/// breaks ties with `tie_breakers` in order. Unscored clips share the last
+/// rank and are not reported as ties.
+pub fn rank(
+    results: &AggregatedResults,
+    tie_breakers: &[TieBreaker],
+    contest_category: Option<&str>,
+) -> (Vec<RankedClip>, Vec<TieResolution>) {
+    let mut clips: Vec<&AggregatedClip> = results
+        .clips
+        .iter()
+        .filter(|clip| {
+            contest_category.map_or(true, |wanted| {
+                clip.contest_category
+                    .as_deref()
+                    .is_some_and(|category| fold(category) == fold(wanted))
+            })
+        })
+        .collect();
+    clips.sort_by(|a, b| compare(a.score, b.score, results.higher_is_better));
+
+    let mut groups: Vec<Vec<&AggregatedClip>> = Vec::new();
+    for clip in clips {
+        match groups.last_mut() {
+            Some(group)
+                if compare(group[0].score, clip.score, results.higher_is_better)
+                    == Ordering::Equal =>
+            {
+                group.push(clip)
+            }
+            _ => groups.push(vec![clip]),
+        }
+    }
+
+    let mut ties = Vec::new();
+    let mut ranked = Vec::new();
+    for group in groups {
+        let rank = ranked.len() as u32 + 1;
+        let runs = if group.len() > 1 && group[0].score.is_some() {
+            split_tie(group, tie_breakers, rank, results, &mut ties)
+        } else {
+            vec![group]
+        };
+        for run in runs {
+            let run_rank = ranked.len() as u32 + 1;
+            let tied = run.len() > 1;
+            ranked.extend(run.into_iter().map(|clip| RankedClip {
+                rank: run_rank,
+                clip_id: clip.clip_id.clone(),
+                label: clip.label.clone(),
+                contest_category: clip.contest_category.clone(),
+                score: clip.score,
+                tied,
+            }));
+        }
+    }
+    (ranked, ties)
+}
+
+/// Aggregates with `method` and ranks; `tie_breakers` defaults to the
+/// project's, then the barème's.
+pub fn ranking(
+    model: &ResultsModel,
+    method: AggregationMethod,
+    tie_breakers: Option<Vec<TieBreaker>>,
+    contest_category: Option<&str>,
+) -> RankingResult {
+    let results = aggregation::aggregate(model, method);
+    let tie_breakers = tie_breakers.unwrap_or_else(|| model.tie_breakers.clone());
+    let (clips, ties) = rank(&results, &tie_breakers, contest_category);
+    RankingResult {
+        method,
+        higher_is_better: results.higher_is_better,
+        tie_breakers,
+        clips,
+        ties,
+    }
+}
+
+pub fn rank_results(
+    data: serde_json::Value,
+    method: AggregationMethod,
+    tie_breakers: Option<Vec<TieBreaker>>,
+    contest_category: Option<String>,
+) -> Result<RankingResult, String> {
+    let model = ResultsModel::from_project(data)?;
+    Ok(ranking(
+        &model,
+        method,
+        tie_breakers,
+        contest_category.as_deref(),
+    ))
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use super::baremes;
+use super::project_listing;
+use super::project_validation;
+use super::types::{Bareme, ProjectData, ScoreValue, TieBreaker};
+
+/// Same fallbacks as `buildCategoryGroups` and `buildJudgeSources` in
+/// `src/utils/results.ts`, so backend exports line up with the results view.
+const DEFAULT_CATEGORY: &str = "Général";
+const DEFAULT_CRITERION_MAX: f64 = 10.0;
+const CURRENT_JUDGE_FALLBACK: &str = "Juge courant";
+const CATEGORY_COLOR_PRESETS: &[&str] = &[
+    "#fb923c", "#a78bfa", "#34d399", "#f59e0b", "#38bdf8", "#fb7185", "#2dd4bf", "#818cf8",
+];
+
+#[derive(Debug, Clone)]
+pub struct ResultCriterion {
+    pub id: String,
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    /// `max` as the barème gives it; scores are capped by it only when set.
+    pub declared_max: Option<f64>,
+    pub category: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultCategory {
+    pub name: String,
+    pub max: f64,
+    pub color: String,
+    /// Indices into `ResultsModel::criteria`.
+    pub criteria: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultClip {
+    pub id: String,
+    /// "Author - Title", as the results table shows it.
+    pub label: String,
+    pub contest_category: Option<String>,
+    pub order: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultJudge {
+    /// `current` for the project's own judge, `imported-<index>` otherwise.
+    pub key: String,
+    pub name: String,
+    /// Clip id -> criterion id -> valid score, clamped to the criterion range.
+    pub scores: HashMap<String, HashMap<String, f64>>,
+}
+
+/// Project scores flattened for exports and aggregation: barème criteria in
+/// order, clips in project order, current judge first.
+#[derive(Debug, Clone)]
+pub struct ResultsModel {
+    pub project_name: String,
+    pub bareme_name: String,
+    pub total_points: f64,
+    pub criteria: Vec<ResultCriterion>,
+    pub categories: Vec<ResultCategory>,
+    pub clips: Vec<ResultClip>,
+    pub judges: Vec<ResultJudge>,
+    /// The project's tie-breakers, else the barème's.
+    pub tie_breakers: Vec<TieBreaker>,
+}
+
+pub fn clip_label(author: Option<&str>, display_name: &str, file_name: &str) -> String {
+    let author = author.map(str::trim).filter(|author| !author.is_empty());
+    let display_name = display_name.trim();
+    match author {
+        Some(author) if !display_name.is_empty() => format!("{} - {}", author, display_name),
+        Some(author) => author.to_string(),
+        None if !display_name.is_empty() => display_name.to_string(),
+        None => file_name.to_string(),
+    }
+}
+
+/// Numeric score of one cell, following `getCriterionNumericScore`: booleans
+/// score the maximum (1 when the barème gives none), invalid or unreadable
+/// values count as missing.
+fn numeric_score(value: &ScoreValue, is_valid: bool, criterion: &ResultCriterion) -> Option<f64> {
+    if !is_valid {
+        return None;
+    }
+    let value = match value {
+        ScoreValue::Bool(checked) => {
+            if *checked {
+                criterion.declared_max.unwrap_or(1.0)
+            } else {
+                0.0
+            }
+        }
+        other => other.as_f64()?,
+    };
+    let max = criterion.declared_max.unwrap_or(value);
+    Some(value.clamp(criterion.min, max.max(criterion.min)))
+}
+
+fn collect_scores<'a>(
+    by_id: &HashMap<&str, &ResultCriterion>,
+    scores: impl Iterator<Item = (&'a String, &'a ScoreValue, bool)>,
+) -> HashMap<String, f64> {
+    scores
+        .filter_map(|(criterion_id, value, is_valid)| {
+            let criterion = by_id.get(criterion_id.as_str())?;
+            Some((
+                criterion_id.clone(),
+                numeric_score(value, is_valid, criterion)?,
+            ))
+        })
+        .collect()
+}
+
+fn project_bareme(root: &serde_json::Value, project: &ProjectData) -> Result<Bareme, String> {
+    let embedded = root
+        .get("bareme")
+        .filter(|bareme| bareme["criteria"].is_array())
+        .cloned();
+    let bareme_id = if project.bareme_id.is_empty() {
+        project.project.bareme_id.as_str()
+    } else {
+        project.bareme_id.as_str()
+    };
+    let value = embedded
+        .or_else(|| baremes::find_bareme(bareme_id))
+        .ok_or_else(|| format!("Bareme {} not found", bareme_id))?;
+    serde_json::from_value(value).map_err(|e| format!("Invalid bareme: {}", e))
+}
+
+impl ResultsModel {
+    pub fn from_project(data: serde_json::Value) -> Result<Self, String> {
+        let normalized = project_validation::normalize_project_value(data, "project")?;
+        let root = project_listing::project_root(&normalized).clone();
+        let project: ProjectData =
+            serde_json::from_value(root.clone()).map_err(|e| format!("Invalid project: {}", e))?;
+        let bareme = project_bareme(&root, &project)?;
+
+        let criteria: Vec<ResultCriterion> = bareme
+            .criteria
+            .iter()
+            .map(|criterion| ResultCriterion {
+                id: criterion.id.clone(),
+                name: criterion.name.clone(),
+                min: criterion.min.unwrap_or(0.0),
+                max: criterion.max.unwrap_or(if criterion.kind == "boolean" {
+                    1.0
+                } else {
+                    DEFAULT_CRITERION_MAX
+                }),
+                declared_max: criterion.max,
+                category: criterion
+                    .category
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|category| !category.is_empty())
+                    .unwrap_or(DEFAULT_CATEGORY)
+                    .to_string(),
+            })
+            .collect();
+
+        let mut categories: Vec<ResultCategory> = Vec::new();
+        for (index, criterion) in criteria.iter().enumerate() {
+            if let Some(category) = categories.iter_mut().find(|c| c.name == criterion.category) {
+                category.criteria.push(index);
+                category.max += criterion.max;
+                continue;
+            }
+            let fallback = CATEGORY_COLOR_PRESETS[categories.len() % CATEGORY_COLOR_PRESETS.len()];
+            let color = bareme
+                .category_colors
+                .as_ref()
+                .and_then(|colors| colors.get(&criterion.category))
+                .filter(|color| color.starts_with('#') && color.len() == 7)
+                .cloned()
+                .unwrap_or_else(|| fallback.to_string());
+            categories.push(ResultCategory {
+                name: criterion.category.clone(),
+                max: criterion.max,
+                color,
+                criteria: vec![index],
+            });
+        }
+
+        let mut clips: Vec<ResultClip> = project
+            .clips
+            .iter()
+            .map(|clip| ResultClip {
+                id: clip.id.clone(),
+                label: clip_label(clip.author.as_deref(), &clip.display_name, &clip.file_name),
+                contest_category: clip
+                    .contest_category
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|category| !category.is_empty())
+                    .map(str::to_string),
+                order: clip.order,
+            })
+            .collect();
+        clips.sort_by_key(|clip| clip.order);
+
+        let by_id: HashMap<&str, &ResultCriterion> = criteria
+            .iter()
+            .map(|criterion| (criterion.id.as_str(), criterion))
+            .collect();
+        let current_scores = project
+            .notes
+            .iter()
+            .map(|(clip_id, note)| {
+                let scores = note
+                    .scores
+                    .iter()
+                    .map(|(id, score)| (id, &score.value, score.is_valid));
+                (clip_id.clone(), collect_scores(&by_id, scores))
+            })
+            .collect();
+        let mut judges = vec![ResultJudge {
+            key: "current".to_string(),
+            name: Some(project.project.judge_name.trim())
+                .filter(|name| !name.is_empty())
+                .unwrap_or(CURRENT_JUDGE_FALLBACK)
+                .to_string(),
+            scores: current_scores,
+        }];
+        for (index, judge) in project.imported_judges.iter().enumerate() {
+            let scores = judge
+                .notes
+                .iter()
+                .map(|(clip_id, note)| {
+                    let scores = note
+                        .scores
+                        .iter()
+                        .map(|(id, score)| (id, &score.value, score.is_valid));
+                    (clip_id.clone(), collect_scores(&by_id, scores))
+                })
+                .collect();
+            judges.push(ResultJudge {
+                key: format!("imported-{}", index),
+                name: judge.judge_name.clone(),
+                scores,
+            });
+        }
+
+        let criteria_total: f64 = criteria.iter().map(|criterion| criterion.max).sum();
+        Ok(ResultsModel {
+            project_name: project.project.name.clone(),
+            bareme_name: bareme.name.clone(),
+            total_points: if bareme.total_points > 0.0 {
+                bareme.total_points
+            } else {
+                criteria_total
+            },
+            criteria,
+            categories,
+            clips,
+            judges,
+            tie_breakers: project
+                .tie_breakers
+                .clone()
+                .or(bareme.tie_breakers.clone())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn score(
+        &self,
+        judge: &ResultJudge,
+        clip_id: &str,
+        criterion: &ResultCriterion,
+    ) -> Option<f64> {
+        judge.scores.get(clip_id)?.get(&criterion.id).copied()
+    }
+
+    /// Sum of the category's scores, `None` when the judge scored none of them.
+    pub fn category_score(
+        &self,
+        judge: &ResultJudge,
+        clip_id: &str,
+        category: &ResultCategory,
+    ) -> Option<f64> {
+        let scores: Vec<f64> = category
+            .criteria
+            .iter()
+            .filter_map(|index| self.score(judge, clip_id, &self.criteria[*index]))
+            .collect();
+        (!scores.is_empty()).then(|| round_score(scores.iter().sum()))
+    }
+
+    /// Judge total for a clip, `None` when the clip was not scored at all.
+    pub fn total(&self, judge: &ResultJudge, clip_id: &str) -> Option<f64> {
+        let scores: Vec<f64> = self
+            .criteria
+            .iter()
+            .filter_map(|criterion| self.score(judge, clip_id, criterion))
+            .collect();
+        (!scores.is_empty()).then(|| round_score(scores.iter().sum()))
+    }
+
+    /// Distinct contest categories of the clips, in clip order.
+    pub fn contest_categories(&self) -> Vec<String> {
+        let mut seen = Vec::new();
+        for clip in &self.clips {
+            if let Some(category) = &clip.contest_category {
+                if !seen.contains(category) {
+                    seen.push(category.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+pub fn round_score(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
@@ -0,0 +1,599 @@
+use rust_xlsxwriter::utility::{column_number_to_name, row_col_to_cell};
+use std::collections::{HashMap, HashSet};
+
+use super::results::{self, ResultClip, ResultJudge, ResultsModel};
+
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// Sheet and column labels. Keys are the French source strings of the
+/// frontend catalog, so the webview can pass its translations as they are.
+pub struct Labels(pub HashMap<String, String>);
+
+impl Labels {
+    pub fn get(&self, key: &str) -> String {
+        self.0
+            .get(key)
+            .filter(|value| !value.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellStyle {
+    Title,
+    Header,
+    /// Category header on its `#rrggbb` colour.
+    Category(String),
+    Name,
+    Score {
+        even: bool,
+    },
+    Total,
+}
+
+#[derive(Debug, Clone)]
+pub enum CellValue {
+    Text(String),
+    Number(f64),
+    Blank,
+    /// Excel A1 syntax with the leading `=`; `cached` is what the cell shows
+    /// until the spreadsheet recalculates, `None` for an empty result.
+    Formula {
+        formula: String,
+        cached: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SheetCell {
+    pub row: u32,
+    pub column: u16,
+    pub value: CellValue,
+    pub style: CellStyle,
+}
+
+/// Merged block; its value is the cell at the top-left corner.
+#[derive(Debug, Clone, Copy)]
+pub struct SheetMerge {
+    pub first_row: u32,
+    pub first_column: u16,
+    pub last_row: u32,
+    pub last_column: u16,
+}
+
+/// One sheet of the results workbook, independent of the file format.
+#[derive(Debug, Clone, Default)]
+pub struct ResultSheet {
+    pub name: String,
+    pub cells: Vec<SheetCell>,
+    pub merges: Vec<SheetMerge>,
+    /// Column widths in characters, as Excel counts them.
+    pub column_widths: Vec<(u16, f64)>,
+    /// First unfrozen row and column.
+    pub freeze: Option<(u32, u16)>,
+}
+
+impl ResultSheet {
+    fn new(name: &str) -> Self {
+        ResultSheet {
+            name: name.to_string(),
+            ..ResultSheet::default()
+        }
+    }
+
+    fn put(&mut self, row: u32, column: u16, value: CellValue, style: &CellStyle) {
+        self.cells.push(SheetCell {
+            row,
+            column,
+            value,
+            style: style.clone(),
+        });
+    }
+
+    fn text(&mut self, row: u32, column: u16, text: impl Into<String>, style: &CellStyle) {
+        self.put(row, column, CellValue::Text(text.into()), style);
+    }
+
+    fn score(&mut self, row: u32, column: u16, value: Option<f64>, style: &CellStyle) {
+        let value = value.map_or(CellValue::Blank, CellValue::Number);
+        self.put(row, column, value, style);
+    }
+
+    fn formula(
+        &mut self,
+        row: u32,
+        column: u16,
+        formula: String,
+        cached: Option<f64>,
+        style: &CellStyle,
+    ) {
+        self.put(row, column, CellValue::Formula { formula, cached }, style);
+    }
+
+    fn merge(
+        &mut self,
+        first_row: u32,
+        first_column: u16,
+        last_row: u32,
+        last_column: u16,
+        text: impl Into<String>,
+        style: &CellStyle,
+    ) {
+        self.text(first_row, first_column, text, style);
+        if last_row > first_row || last_column > first_column {
+            self.merges.push(SheetMerge {
+                first_row,
+                first_column,
+                last_row,
+                last_column,
+            });
+        }
+    }
+
+    fn width(&mut self, column: u16, width: f64) {
+        self.column_widths.push((column, width));
+    }
+
+    /// Whether the cell sits inside a merge without being its top-left corner.
+    pub fn is_covered(&self, row: u32, column: u16) -> bool {
+        self.merges.iter().any(|merge| {
+            (merge.first_row..=merge.last_row).contains(&row)
+                && (merge.first_column..=merge.last_column).contains(&column)
+                && (row, column) != (merge.first_row, merge.first_column)
+        })
+    }
+
+    pub fn merge_at(&self, row: u32, column: u16) -> Option<&SheetMerge> {
+        self.merges
+            .iter()
+            .find(|merge| (merge.first_row, merge.first_column) == (row, column))
+    }
+}
+
+/// Same rules as `sanitizeSheetName` in `xlsxWorkbook.ts`, plus
+/// case-insensitive uniqueness across the workbook.
+fn unique_sheet_name(value: &str, fallback: &str, used: &mut HashSet<String>) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|ch| match ch {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => ' ',
+            other => other,
+        })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned = cleaned.trim_matches('\'').trim();
+    let base = if cleaned.is_empty() {
+        fallback
+    } else {
+        cleaned
+    };
+    let base: String = base.chars().take(MAX_SHEET_NAME_LEN).collect();
+
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while !used.insert(name.to_lowercase()) {
+        let tail = format!(" ({})", suffix);
+        let keep = MAX_SHEET_NAME_LEN - tail.chars().count();
+        name = format!("{}{}", base.chars().take(keep).collect::<String>(), tail);
+        suffix += 1;
+    }
+    name
+}
+
+fn sheet_ref(sheet: &str, row: u32, column: u16) -> String {
+    format!(
+        "'{}'!{}",
+        sheet.replace('\'', "''"),
+        row_col_to_cell(row, column)
+    )
+}
+
+fn range(row: u32, first_column: u16, last_column: u16) -> String {
+    format!(
+        "{}:{}",
+        row_col_to_cell(row, first_column),
+        row_col_to_cell(row, last_column)
+    )
+}
+
+fn row_col_to_cell_abs(row: u32, column: u16) -> String {
+    format!("${}${}", column_number_to_name(column), row + 1)
+}
+
+fn score_style(index: usize) -> CellStyle {
+    CellStyle::Score {
+        even: index % 2 == 0,
+    }
+}
+
+/// Where each judge's subtotals and total landed, for cross-sheet formulas.
+struct JudgeSheet {
+    name: String,
+    /// Clip id -> 0-based row.
+    rows: HashMap<String, u32>,
+    category_columns: Vec<u16>,
+    total_column: u16,
+}
+
+/// One sheet per judge: criteria grouped by category, then one `SUM` column per
+/// category and a total summing those, so every aggregate can be audited.
+fn judge_sheet(
+    model: &ResultsModel,
+    judge: &ResultJudge,
+    sheet_name: &str,
+    labels: &Labels,
+) -> (ResultSheet, JudgeSheet) {
+    let mut sheet = ResultSheet::new(sheet_name);
+    let criterion_count = model.criteria.len() as u16;
+    let first_subtotal = criterion_count + 1;
+    let total_column = first_subtotal + model.categories.len() as u16;
+
+    sheet.merge(
+        0,
+        0,
+        1,
+        0,
+        format!("{}: {}", labels.get("Juge"), judge.name),
+        &CellStyle::Title,
+    );
+    sheet.width(0, 30.0);
+
+    // Criteria ordered by category so each category spans a contiguous range.
+    let mut column: u16 = 1;
+    let mut category_ranges = Vec::new();
+    for category in &model.categories {
+        let style = CellStyle::Category(category.color.clone());
+        let first = column;
+        for index in &category.criteria {
+            let criterion = &model.criteria[*index];
+            sheet.text(
+                1,
+                column,
+                format!("{} /{}", criterion.name, criterion.max),
+                &style,
+            );
+            sheet.width(
+                column,
+                (criterion.name.chars().count() + 4).clamp(12, 28) as f64,
+            );
+            column += 1;
+        }
+        sheet.merge(
+            0,
+            first,
+            0,
+            column - 1,
+            format!("{} /{}", category.name, category.max),
+            &style,
+        );
+        category_ranges.push((first, column - 1));
+    }
+
+    for (index, category) in model.categories.iter().enumerate() {
+        let subtotal_column = first_subtotal + index as u16;
+        sheet.merge(
+            0,
+            subtotal_column,
+            1,
+            subtotal_column,
+            format!("{} /{}", category.name, category.max),
+            &CellStyle::Category(category.color.clone()),
+        );
+        sheet.width(subtotal_column, 14.0);
+    }
+    sheet.merge(
+        0,
+        total_column,
+        1,
+        total_column,
+        format!("{} /{}", labels.get("Total"), model.total_points),
+        &CellStyle::Header,
+    );
+    sheet.width(total_column, 12.0);
+
+    let mut rows = HashMap::new();
+    for (index, clip) in model.clips.iter().enumerate() {
+        let row = index as u32 + 2;
+        let style = score_style(index);
+        rows.insert(clip.id.clone(), row);
+        sheet.text(row, 0, &clip.label, &CellStyle::Name);
+
+        let mut column: u16 = 1;
+        for category in &model.categories {
+            for criterion_index in &category.criteria {
+                let value = model.score(judge, &clip.id, &model.criteria[*criterion_index]);
+                sheet.score(row, column, value, &style);
+                column += 1;
+            }
+        }
+
+        for (category_index, (category, (first, last))) in
+            model.categories.iter().zip(&category_ranges).enumerate()
+        {
+            sheet.formula(
+                row,
+                first_subtotal + category_index as u16,
+                format!(
+                    "=IF(COUNT({cells})=0,\"\",SUM({cells}))",
+                    cells = range(row, *first, *last)
+                ),
+                model.category_score(judge, &clip.id, category),
+                &style,
+            );
+        }
+
+        sheet.formula(
+            row,
+            total_column,
+            format!(
+                "=IF(COUNT({})=0,\"\",SUM({}))",
+                range(row, 1, criterion_count.max(1)),
+                range(row, first_subtotal, total_column - 1)
+            ),
+            model.total(judge, &clip.id),
+            &CellStyle::Total,
+        );
+    }
+
+    sheet.freeze = Some((2, 1));
+    let layout = JudgeSheet {
+        name: sheet_name.to_string(),
+        rows,
+        category_columns: (first_subtotal..total_column).collect(),
+        total_column,
+    };
+    (sheet, layout)
+}
+
+/// Source of the per-judge values in a ranking: the judge total or one
+/// category subtotal.
+#[derive(Clone, Copy)]
+enum RankedValue {
+    Total,
+    Category(usize),
+}
+
+fn judge_value(
+    model: &ResultsModel,
+    judge: &ResultJudge,
+    clip: &ResultClip,
+    value: RankedValue,
+) -> Option<f64> {
+    match value {
+        RankedValue::Total => model.total(judge, &clip.id),
+        RankedValue::Category(index) => {
+            model.category_score(judge, &clip.id, &model.categories[index])
+        }
+    }
+}
+
+fn average(model: &ResultsModel, clip: &ResultClip, value: RankedValue) -> Option<f64> {
+    let values: Vec<f64> = model
+        .judges
+        .iter()
+        .filter_map(|judge| judge_value(model, judge, clip, value))
+        .collect();
+    results::mean(&values).map(results::round_score)
+}
+
+/// Writes a ranking table at `start_row`: rank, participant, optional contest
+/// category, one column per judge referencing the judge sheets, and the
+/// rounded average. Returns the next free row.
+#[allow(clippy::too_many_arguments)]
+fn ranking_table(
+    sheet: &mut ResultSheet,
+    model: &ResultsModel,
+    clips: &[&ResultClip],
+    judge_sheets: &[JudgeSheet],
+    value: RankedValue,
+    title: &str,
+    start_row: u32,
+    labels: &Labels,
+) -> u32 {
+    let show_contest = clips.iter().any(|clip| clip.contest_category.is_some());
+    let judge_column: u16 = if show_contest { 3 } else { 2 };
+    let average_column = judge_column + model.judges.len() as u16;
+    let max = match value {
+        RankedValue::Total => model.total_points,
+        RankedValue::Category(index) => model.categories[index].max,
+    };
+
+    sheet.merge(
+        start_row,
+        0,
+        start_row,
+        average_column,
+        title,
+        &CellStyle::Title,
+    );
+    let header_row = start_row + 1;
+    let mut headers = vec![labels.get("Rang"), labels.get("Participant")];
+    if show_contest {
+        headers.push(labels.get("Catégorie"));
+    }
+    headers.extend(model.judges.iter().map(|judge| judge.name.clone()));
+    headers.push(format!("{} /{}", labels.get("Moy."), max));
+    for (column, header) in headers.into_iter().enumerate() {
+        sheet.text(header_row, column as u16, header, &CellStyle::Header);
+    }
+
+    let mut ranked: Vec<(&ResultClip, Option<f64>)> = clips
+        .iter()
+        .map(|clip| (*clip, average(model, clip, value)))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.unwrap_or(f64::NEG_INFINITY)
+            .total_cmp(&a.1.unwrap_or(f64::NEG_INFINITY))
+            .then(a.0.order.cmp(&b.0.order))
+    });
+
+    let first_data_row = header_row + 1;
+    let last_data_row = header_row + ranked.len().max(1) as u32;
+    let average_range = format!(
+        "{}:{}",
+        row_col_to_cell_abs(first_data_row, average_column),
+        row_col_to_cell_abs(last_data_row, average_column)
+    );
+    for (index, (clip, clip_average)) in ranked.iter().enumerate() {
+        let row = first_data_row + index as u32;
+        let style = score_style(index);
+        let average_cell = row_col_to_cell(row, average_column);
+        let rank = clip_average.map(|own| {
+            1 + ranked
+                .iter()
+                .filter(|(_, other)| other.is_some_and(|other| other > own))
+                .count()
+        });
+        sheet.formula(
+            row,
+            0,
+            format!(
+                "=IF({cell}=\"\",\"\",RANK({cell},{range}))",
+                cell = average_cell,
+                range = average_range
+            ),
+            rank.map(|rank| rank as f64),
+            &style,
+        );
+        sheet.text(row, 1, &clip.label, &CellStyle::Name);
+        if show_contest {
+            sheet.text(
+                row,
+                2,
+                clip.contest_category.as_deref().unwrap_or(""),
+                &style,
+            );
+        }
+
+        for (judge_index, (judge, layout)) in model.judges.iter().zip(judge_sheets).enumerate() {
+            let source_row = layout.rows[&clip.id];
+            let source_column = match value {
+                RankedValue::Total => layout.total_column,
+                RankedValue::Category(index) => layout.category_columns[index],
+            };
+            sheet.formula(
+                row,
+                judge_column + judge_index as u16,
+                format!("={}", sheet_ref(&layout.name, source_row, source_column)),
+                judge_value(model, judge, clip, value),
+                &style,
+            );
+        }
+
+        sheet.formula(
+            row,
+            average_column,
+            format!(
+                "=IF(COUNT({judges})=0,\"\",ROUND(AVERAGE({judges}),2))",
+                judges = range(row, judge_column, average_column - 1)
+            ),
+            *clip_average,
+            &CellStyle::Total,
+        );
+    }
+
+    last_data_row + 2
+}
+
+fn ranking_widths(sheet: &mut ResultSheet, model: &ResultsModel) {
+    sheet.width(0, 7.0);
+    sheet.width(1, 34.0);
+    for column in 2..=3 + model.judges.len() as u16 {
+        sheet.width(column, 14.0);
+    }
+}
+
+/// Lays out the results workbook: global ranking, per-category tables, one
+/// tab per contest category and one sheet per judge. Totals, subtotals,
+/// averages and ranks are formulas with cached values.
+pub fn build_sheets(model: &ResultsModel, labels: &Labels) -> Vec<ResultSheet> {
+    let mut used = HashSet::new();
+    let ranking_name = unique_sheet_name(&labels.get("Classement"), "Classement", &mut used);
+    let categories_name = unique_sheet_name(&labels.get("Catégories"), "Catégories", &mut used);
+    let contest_names: Vec<(String, String)> = model
+        .contest_categories()
+        .into_iter()
+        .map(|category| {
+            let name = unique_sheet_name(&category, "Catégorie", &mut used);
+            (category, name)
+        })
+        .collect();
+
+    // Summary sheets come first in the workbook but reference the judge
+    // sheets, whose layout has to be known before they are written.
+    let (judge_sheets, judge_layouts): (Vec<ResultSheet>, Vec<JudgeSheet>) = model
+        .judges
+        .iter()
+        .enumerate()
+        .map(|(index, judge)| {
+            let name = unique_sheet_name(
+                &judge.name,
+                &format!("{} {}", labels.get("Juge"), index + 1),
+                &mut used,
+            );
+            judge_sheet(model, judge, &name, labels)
+        })
+        .unzip();
+
+    let all_clips: Vec<&ResultClip> = model.clips.iter().collect();
+    let mut sheets = Vec::new();
+
+    let mut ranking = ResultSheet::new(&ranking_name);
+    let title = format!("{} — {}", labels.get("Classement"), model.project_name);
+    ranking_table(
+        &mut ranking,
+        model,
+        &all_clips,
+        &judge_layouts,
+        RankedValue::Total,
+        title.trim_end_matches(" — "),
+        0,
+        labels,
+    );
+    ranking_widths(&mut ranking, model);
+    ranking.freeze = Some((2, 2));
+    sheets.push(ranking);
+
+    let mut categories = ResultSheet::new(&categories_name);
+    let mut row = 0;
+    for (index, category) in model.categories.iter().enumerate() {
+        row = ranking_table(
+            &mut categories,
+            model,
+            &all_clips,
+            &judge_layouts,
+            RankedValue::Category(index),
+            &format!("{} /{}", category.name, category.max),
+            row,
+            labels,
+        );
+    }
+    ranking_widths(&mut categories, model);
+    sheets.push(categories);
+
+    for (category, name) in &contest_names {
+        let clips: Vec<&ResultClip> = model
+            .clips
+            .iter()
+            .filter(|clip| clip.contest_category.as_deref() == Some(category.as_str()))
+            .collect();
+        let mut sheet = ResultSheet::new(name);
+        ranking_table(
+            &mut sheet,
+            model,
+            &clips,
+            &judge_layouts,
+            RankedValue::Total,
+            category,
+            0,
+            labels,
+        );
+        ranking_widths(&mut sheet, model);
+        sheet.freeze = Some((2, 2));
+        sheets.push(sheet);
+    }
+
+    sheets.extend(judge_sheets);
+    sheets
+}
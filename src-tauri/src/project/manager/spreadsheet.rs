@@ -0,0 +1,133 @@
+use calamine::{open_workbook_auto, Reader};
+use std::fs;
+use std::path::Path;
+
+/// Cells of the first sheet as text, row by row. Rows keep their own length;
+/// callers index with `cell` to read past the end of a short row.
+pub type SheetRows = Vec<Vec<String>>;
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Spreadsheet exports from Google Sheets use `,`, French Excel uses `;`.
+fn guess_delimiter(content: &str) -> u8 {
+    let first_line = content.lines().next().unwrap_or("");
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|delimiter| first_line.bytes().filter(|byte| byte == delimiter).count())
+        .unwrap_or(b',')
+}
+
+fn read_delimited(path: &Path, delimiter: Option<u8>) -> Result<SheetRows, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read sheet: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let content = content.trim_start_matches('\u{feff}');
+    let delimiter = delimiter.unwrap_or_else(|| guess_delimiter(content));
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|e| format!("Failed to parse sheet: {}", e))
+        })
+        .collect()
+}
+
+fn workbook_sheet(
+    workbook: &mut calamine::Sheets<std::io::BufReader<fs::File>>,
+    sheet: &str,
+) -> Result<SheetRows, String> {
+    let range = workbook
+        .worksheet_range(sheet)
+        .map_err(|e| format!("Failed to read sheet {}: {}", sheet, e))?;
+
+    // A range starts at its first used cell; pad so indices match the sheet.
+    let (first_row, first_column) = range.start().unwrap_or((0, 0));
+    let mut rows: SheetRows = vec![Vec::new(); first_row as usize];
+    rows.extend(range.rows().map(|row| {
+        std::iter::repeat(String::new())
+            .take(first_column as usize)
+            .chain(row.iter().map(|cell| cell.to_string()))
+            .collect()
+    }));
+    Ok(rows)
+}
+
+fn read_workbook(path: &Path, all_sheets: bool) -> Result<Vec<(String, SheetRows)>, String> {
+    let mut workbook =
+        open_workbook_auto(path).map_err(|e| format!("Failed to open workbook: {}", e))?;
+    let mut names = workbook.sheet_names();
+    if names.is_empty() {
+        return Err("The workbook has no sheet".to_string());
+    }
+    if !all_sheets {
+        names.truncate(1);
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            let rows = workbook_sheet(&mut workbook, &name)?;
+            Ok((name, rows))
+        })
+        .collect()
+}
+
+fn read(path: &Path, all_sheets: bool) -> Result<Vec<(String, SheetRows)>, String> {
+    if !path.is_file() {
+        return Err(format!("Sheet not found: {}", path.display()));
+    }
+    let single = |rows: SheetRows| {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        vec![(name, rows)]
+    };
+    match extension(path).as_str() {
+        "csv" | "txt" => read_delimited(path, None).map(single),
+        "tsv" | "tab" => read_delimited(path, Some(b'\t')).map(single),
+        "xlsx" | "xlsm" | "xls" | "ods" => read_workbook(path, all_sheets),
+        other => Err(format!("Unsupported sheet format: .{}", other)),
+    }
+}
+
+/// Reads a CSV, TSV, XLSX, XLS or ODS sheet; workbooks are read from their
+/// first sheet.
+pub fn read_rows(path: &Path) -> Result<SheetRows, String> {
+    let mut sheets = read(path, false)?;
+    Ok(sheets.remove(0).1)
+}
+
+/// Every sheet of a workbook with its name; CSV and TSV files are a single
+/// sheet named after the file.
+pub fn read_sheets(path: &Path) -> Result<Vec<(String, SheetRows)>, String> {
+    read(path, true)
+}
+
+/// Trimmed cell text, empty past the end of the row.
+pub fn cell(row: &[String], column: usize) -> &str {
+    row.get(column).map_or("", |value| value.trim())
+}
+
+/// Parses a score cell; French sheets write `3,5` for 3.5.
+pub fn parse_decimal(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    value
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
@@ -1,45 +1,708 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Unknown keys are kept in `extra` on every object so that fields written by a
+/// newer frontend survive a round-trip through the backend.
+type ExtraFields = serde_json::Map<String, serde_json::Value>;
+pub type TextMap = BTreeMap<String, String>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ProjectData {
+    #[serde(default = "default_version")]
     pub version: String,
     pub project: ProjectInfo,
+    #[serde(default, alias = "bareme_id")]
     pub bareme_id: String,
+    #[serde(default)]
     pub clips: Vec<ClipInfo>,
-    pub notes: serde_json::Value,
+    #[serde(default)]
+    pub notes: BTreeMap<String, NoteData>,
+    #[serde(default, alias = "imported_judges")]
+    pub imported_judges: Vec<ImportedJudgeData>,
+    /// Barème revision the scores were given with.
+    #[serde(
+        default,
+        alias = "bareme_revision",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bareme_revision: Option<BaremeRevisionPin>,
+    /// Overrides the barème's tie-breakers for this contest.
+    #[serde(
+        default,
+        alias = "tie_breakers",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tie_breakers: Option<Vec<TieBreaker>>,
+    /// Set while judges score under entry codes instead of names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blind: Option<BlindMode>,
+    /// How the judges' clip orders are drawn, kept to reproduce them.
+    #[serde(default, alias = "clip_order", skip_serializing_if = "Option::is_none")]
+    pub clip_order: Option<ClipOrderSettings>,
+    /// Last entry of the audit log when the project was saved.
+    #[serde(default, alias = "audit_head", skip_serializing_if = "Option::is_none")]
+    pub audit_head: Option<AuditHead>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+fn default_version() -> String {
+    "1.0".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ProjectInfo {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub name: String,
+    #[serde(default, alias = "judge_name")]
+    pub judge_name: String,
+    #[serde(default, alias = "created_at")]
     pub created_at: String,
+    #[serde(default, alias = "updated_at")]
     pub updated_at: String,
+    #[serde(default, alias = "bareme_id")]
     pub bareme_id: String,
+    #[serde(default, alias = "clips_folder_path")]
     pub clips_folder_path: String,
+    #[serde(default)]
     pub settings: ProjectSettings,
+    #[serde(default, alias = "result_notes")]
+    pub result_notes: TextMap,
+    #[serde(default, alias = "file_path", skip_serializing_if = "Option::is_none")]
     pub file_path: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
 pub struct ProjectSettings {
+    #[serde(alias = "auto_save")]
     pub auto_save: bool,
-    pub auto_save_interval: u32,
+    #[serde(alias = "auto_save_interval")]
+    pub auto_save_interval: f64,
+    #[serde(alias = "default_playback_speed")]
     pub default_playback_speed: f64,
+    #[serde(alias = "default_volume")]
     pub default_volume: f64,
+    #[serde(alias = "judge_colors")]
+    pub judge_colors: TextMap,
+    #[serde(alias = "hide_final_score_until_end")]
     pub hide_final_score_until_end: bool,
+    #[serde(alias = "hide_totals")]
+    pub hide_totals: bool,
+    #[serde(alias = "show_miniatures")]
+    pub show_miniatures: bool,
+    #[serde(alias = "show_quick_actions")]
+    pub show_quick_actions: bool,
+    #[serde(alias = "multi_pseudo_display_mode")]
+    pub multi_pseudo_display_mode: String,
+    #[serde(alias = "clip_name_pattern")]
+    pub clip_name_pattern: String,
+    #[serde(alias = "thumbnail_default_time_sec")]
+    pub thumbnail_default_time_sec: f64,
+    #[serde(alias = "contest_categories_enabled")]
+    pub contest_categories_enabled: bool,
+    #[serde(alias = "contest_category_presets")]
+    pub contest_category_presets: Vec<String>,
+    #[serde(alias = "contest_category_colors")]
+    pub contest_category_colors: TextMap,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Mirrors `DEFAULT_PROJECT_SETTINGS` in `src/types/project.ts`.
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            auto_save: true,
+            auto_save_interval: 30.0,
+            default_playback_speed: 1.0,
+            default_volume: 80.0,
+            judge_colors: TextMap::new(),
+            hide_final_score_until_end: false,
+            hide_totals: false,
+            show_miniatures: true,
+            show_quick_actions: true,
+            multi_pseudo_display_mode: "all".to_string(),
+            clip_name_pattern: "pseudo_clip".to_string(),
+            thumbnail_default_time_sec: 10.0,
+            contest_categories_enabled: false,
+            contest_category_presets: Vec::new(),
+            contest_category_colors: TextMap::new(),
+            extra: ExtraFields::new(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ClipInfo {
+    #[serde(default)]
     pub id: String,
+    #[serde(default, alias = "file_name")]
     pub file_name: String,
+    #[serde(default, alias = "file_path")]
     pub file_path: String,
+    #[serde(default, alias = "display_name")]
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(
+        default,
+        alias = "contest_category",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contest_category: Option<String>,
+    #[serde(default)]
     pub duration: f64,
+    #[serde(default, alias = "has_internal_subtitles")]
     pub has_internal_subtitles: bool,
+    #[serde(default = "default_audio_track_count", alias = "audio_track_count")]
     pub audio_track_count: u32,
+    #[serde(default)]
     pub scored: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    #[serde(
+        default,
+        alias = "favorite_comment",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub favorite_comment: Option<String>,
+    #[serde(default)]
     pub order: u32,
+    #[serde(
+        default,
+        alias = "thumbnail_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub thumbnail_time: Option<f64>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+fn default_audio_track_count() -> u32 {
+    1
+}
+
+/// A criterion value as typed by the judge: `number | string | boolean`.
+/// `Empty` covers the `null` the webview writes for a cleared or `NaN` value.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(untagged)]
+pub enum ScoreValue {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    #[default]
+    Empty,
+}
+
+impl ScoreValue {
+    /// Numeric view used by aggregation; text values such as `"3,5"` are parsed
+    /// with a comma decimal separator accepted.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ScoreValue::Number(value) => Some(*value).filter(|value| value.is_finite()),
+            ScoreValue::Text(text) => text
+                .trim()
+                .replace(',', ".")
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite()),
+            ScoreValue::Bool(_) | ScoreValue::Empty => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteData {
+    #[serde(default, alias = "clip_id")]
+    pub clip_id: String,
+    #[serde(default, alias = "bareme_id")]
+    pub bareme_id: String,
+    #[serde(default)]
+    pub scores: BTreeMap<String, CriterionScoreData>,
+    #[serde(default, alias = "text_notes")]
+    pub text_notes: String,
+    #[serde(
+        default,
+        alias = "criterion_notes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub criterion_notes: Option<TextMap>,
+    #[serde(
+        default,
+        alias = "category_notes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub category_notes: Option<TextMap>,
+    #[serde(
+        default,
+        alias = "final_score",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub final_score: Option<f64>,
+    #[serde(default, alias = "scored_at", skip_serializing_if = "Option::is_none")]
+    pub scored_at: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CriterionScoreData {
+    #[serde(default, alias = "criterion_id")]
+    pub criterion_id: String,
+    #[serde(default)]
+    pub value: ScoreValue,
+    #[serde(default = "default_true", alias = "is_valid")]
+    pub is_valid: bool,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedJudgeData {
+    #[serde(alias = "judge_name")]
+    pub judge_name: String,
+    #[serde(default)]
+    pub notes: BTreeMap<String, ImportedJudgeNote>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedJudgeNote {
+    #[serde(default)]
+    pub scores: BTreeMap<String, ImportedJudgeCriterionScore>,
+    #[serde(
+        default,
+        alias = "final_score",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub final_score: Option<f64>,
+    #[serde(default, alias = "text_notes", skip_serializing_if = "Option::is_none")]
+    pub text_notes: Option<String>,
+    #[serde(
+        default,
+        alias = "criterion_notes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub criterion_notes: Option<TextMap>,
+    #[serde(
+        default,
+        alias = "category_notes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub category_notes: Option<TextMap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    #[serde(
+        default,
+        alias = "favorite_comment",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub favorite_comment: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedJudgeCriterionScore {
+    #[serde(default)]
+    pub value: ScoreValue,
+    #[serde(default = "default_true", alias = "is_valid")]
+    pub is_valid: bool,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Mirror of `Bareme` in `src/types/bareme.ts`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Bareme {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, alias = "is_official")]
+    pub is_official: bool,
+    #[serde(
+        default,
+        alias = "hide_totals_until_all_scored",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hide_totals_until_all_scored: Option<bool>,
+    #[serde(default)]
+    pub criteria: Vec<Criterion>,
+    #[serde(
+        default,
+        alias = "category_colors",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub category_colors: Option<TextMap>,
+    #[serde(default, alias = "total_points")]
+    pub total_points: f64,
+    #[serde(default, alias = "created_at")]
+    pub created_at: String,
+    #[serde(default, alias = "updated_at")]
+    pub updated_at: String,
+    /// Set on save from the revision history; see `bareme_revisions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u32>,
+    #[serde(
+        default,
+        alias = "content_hash",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub content_hash: Option<String>,
+    /// Applied in order to clips left tied by the aggregated score.
+    #[serde(
+        default,
+        alias = "tie_breakers",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tie_breakers: Option<Vec<TieBreaker>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Criterion {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `numeric`, `slider`, `boolean`, `select` or `text`.
+    #[serde(rename = "type", default = "default_criterion_type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    #[serde(default = "default_true")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(
+        default,
+        alias = "validation_rules",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub validation_rules: Option<Vec<CriterionValidationRule>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+fn default_criterion_type() -> String {
+    "numeric".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CriterionValidationRule {
+    /// `min`, `max`, `required` or `step`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: serde_json::Value,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// One problem found in a barème. `criterion_id` is set for per-criterion
+/// problems so the editor can flag the row.
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeValidationIssue {
+    pub path: String,
+    pub criterion_id: Option<String>,
+    pub message: String,
+}
+
+/// Barème revision a project was scored with. `revision` is missing when the
+/// embedded barème matches no stored revision, e.g. one imported from a file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BaremeRevisionPin {
+    pub bareme_id: String,
+    #[serde(default)]
+    pub revision: Option<u32>,
+    #[serde(default)]
+    pub content_hash: String,
+}
+
+/// `mismatch` is set when the barème changed since the project was pinned.
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeRevisionPinStatus {
+    pub pinned: Option<BaremeRevisionPin>,
+    pub current: Option<BaremeRevisionPin>,
+    pub mismatch: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeRevision {
+    pub revision: u32,
+    pub content_hash: String,
+    pub name: String,
+    pub revised_at: String,
+    pub total_points: f64,
+    pub criteria_count: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeCriterionChange {
+    pub criterion_id: String,
+    pub name: String,
+    pub previous_name: Option<String>,
+    pub from_min: Option<f64>,
+    pub from_max: Option<f64>,
+    pub from_step: Option<f64>,
+    pub to_min: Option<f64>,
+    pub to_max: Option<f64>,
+    pub to_step: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeRevisionDiff {
+    pub bareme_id: String,
+    pub from_revision: u32,
+    pub to_revision: u32,
+    pub from_total_points: f64,
+    pub to_total_points: f64,
+    pub added: Vec<BaremeCriterionChange>,
+    pub removed: Vec<BaremeCriterionChange>,
+    /// Criteria whose min, max or step changed.
+    pub rescaled: Vec<BaremeCriterionChange>,
+    pub renamed: Vec<BaremeCriterionChange>,
+}
+
+/// Barème read from a contest sheet header. `duplicate_of` is set when the
+/// folder already had the same grid and that barème was returned instead.
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportedSheetBareme {
+    pub bareme: serde_json::Value,
+    pub duplicate_of: Option<String>,
+    pub judge_name: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSheetClipCandidate {
+    pub clip_id: String,
+    pub display_name: String,
+    pub author: Option<String>,
+    /// 0-100, higher is a safer match.
+    pub score: u8,
+}
+
+/// One score row of a judge sheet. `row` is the 1-based sheet row; `clip_id`
+/// is set for matched rows only.
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSheetRow {
+    pub row: usize,
+    pub label: String,
+    pub clip_id: Option<String>,
+    pub candidates: Vec<JudgeSheetClipCandidate>,
+}
+
+/// Sheet column and the barème criterion it was mapped to, if any.
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSheetColumn {
+    pub column_name: String,
+    pub sheet_max: f64,
+    pub criterion_id: Option<String>,
+    pub criterion_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSheetValueIssue {
+    pub row: usize,
+    pub label: String,
+    pub criterion_id: String,
+    pub value: String,
+    pub message: String,
+}
+
+/// Preview of a judge sheet import. `judge` is what would be added to the
+/// project's imported judges; nothing is written until the frontend does so.
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSheetImport {
+    pub judge: Option<ImportedJudgeData>,
+    pub judge_name: String,
+    pub columns: Vec<JudgeSheetColumn>,
+    pub matched: Vec<JudgeSheetRow>,
+    pub unmatched: Vec<JudgeSheetRow>,
+    pub ambiguous: Vec<JudgeSheetRow>,
+    /// Out-of-range and unreadable values; out-of-range ones are kept as invalid.
+    pub value_issues: Vec<JudgeSheetValueIssue>,
+}
+
+/// One judge file of a merge. `judge_name` is the name the judge was added
+/// under; `unmatched_clips` are the file names of clips the judge scored that
+/// the project does not have.
+#[derive(Debug, Serialize, Clone)]
+pub struct MergedJudge {
+    pub source: String,
+    pub judge_name: String,
+    pub matched_clips: usize,
+    pub unmatched_clips: Vec<String>,
+}
+
+/// Several files under one judge name; all but the first were renamed.
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateJudgeName {
+    pub judge_name: String,
+    pub sources: Vec<String>,
+    pub renamed_to: Vec<String>,
+}
+
+/// A judge file scored with another barème, or another revision of it, than
+/// the merged project. Hashes are empty when the file does not record one.
+#[derive(Debug, Serialize, Clone)]
+pub struct BaremeMismatch {
+    pub source: String,
+    pub judge_name: String,
+    pub bareme_id: String,
+    pub content_hash: String,
+    pub expected_bareme_id: String,
+    pub expected_content_hash: String,
+}
+
+/// A clip some judges scored and others did not.
+#[derive(Debug, Serialize, Clone)]
+pub struct PartialClip {
+    pub clip_id: String,
+    pub label: String,
+    pub scored_by: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct JudgeMergeConflicts {
+    pub duplicate_judges: Vec<DuplicateJudgeName>,
+    pub bareme_mismatches: Vec<BaremeMismatch>,
+    pub partial_clips: Vec<PartialClip>,
+}
+
+/// Project with the judge files added to its imported judges, not saved.
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeMerge {
+    pub project: serde_json::Value,
+    pub judges: Vec<MergedJudge>,
+    pub conflicts: JudgeMergeConflicts,
+}
+
+/// Blind judging: clips carry an entry code instead of their name, author
+/// and file. `salt` feeds the derivation of the sealing key from the
+/// organizer key, which is never stored.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlindMode {
+    pub salt: String,
+    #[serde(default)]
+    pub entries: Vec<BlindEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlindEntry {
+    pub code: String,
+    #[serde(alias = "clip_id")]
+    pub clip_id: String,
+    /// Size and quick content hash, to find the video without its name.
+    #[serde(default, alias = "file_size")]
+    pub file_size: u64,
+    #[serde(default, alias = "content_hash")]
+    pub content_hash: String,
+    /// The clip's original identity, sealed with the organizer key.
+    pub sealed: String,
+}
+
+/// A clip's identity behind its entry code, revealed with the organizer key.
+#[derive(Debug, Serialize, Clone)]
+pub struct BlindIdentity {
+    pub code: String,
+    pub clip_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub display_name: String,
+    pub author: Option<String>,
+}
+
+/// Entry codes whose video was found in the clips folder, and those missing.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BlindSessionStatus {
+    pub matched: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipOrderMethod {
+    /// An independent shuffle per judge, drawn from the seed and their name.
+    #[default]
+    Shuffle,
+    /// Balanced Latin square over the panel: each clip comes at each position,
+    /// and right after each other clip, equally often.
+    LatinSquare,
+}
+
+/// Seed and options the clip orders are drawn with; the same settings give
+/// the same orders on any machine.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipOrderSettings {
+    #[serde(default)]
+    pub method: ClipOrderMethod,
+    /// Any text; a random one is drawn when empty.
+    #[serde(default)]
+    pub seed: String,
+    /// Orders each contest category on its own, categories one after another.
+    #[serde(default, alias = "group_by_category")]
+    pub group_by_category: bool,
+    /// The panel, in Latin-square row order.
+    #[serde(default)]
+    pub judges: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeClipOrder {
+    pub judge_name: String,
+    pub clip_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClipOrderPlan {
+    pub settings: ClipOrderSettings,
+    pub orders: Vec<JudgeClipOrder>,
+}
+
+/// One problem found while validating a project file. `path` points at the
+/// offending value, e.g. `clips[3].id` or `notes.clip-1.scores.encoding.value`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectValidationIssue {
+    pub path: String,
+    pub message: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -48,4 +711,374 @@ pub struct ProjectSummary {
     pub judge_name: String,
     pub updated_at: String,
     pub file_path: String,
+    pub clip_count: usize,
+    pub scored_count: usize,
+    pub completion_percent: f64,
+    pub bareme_id: String,
+    pub bareme_name: String,
+    pub imported_judge_count: usize,
+    pub size_bytes: u64,
+    /// Sub-folder of the listed root holding the file, empty at the top level.
+    pub contest: String,
+    /// Set when the file could not be read or parsed as JSON.
+    pub parse_error: Option<String>,
+}
+
+/// Payload of the `project:external-change` and `baremes:external-change`
+/// events, emitted when a watched file is changed by another program.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExternalFileChange {
+    /// `project` or `bareme`.
+    pub scope: String,
+    /// `modified`, `created`, `deleted` or `renamed`.
+    pub kind: String,
+    pub path: String,
+    pub new_path: Option<String>,
+    pub old_modified: Option<String>,
+    pub new_modified: Option<String>,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+}
+
+/// A barème file moved to `Baremes/.trash` by the dedup or a delete.
+#[derive(Debug, Serialize)]
+pub struct QuarantinedBareme {
+    pub id: String,
+    pub original_file_name: String,
+    pub bareme_id: String,
+    pub bareme_name: String,
+    pub reason: String,
+    pub quarantined_at: String,
+    pub size_bytes: u64,
+}
+
+/// Holder of a project lock as shown to the user.
+#[derive(Debug, Serialize)]
+pub struct ProjectLockInfo {
+    pub judge_name: String,
+    pub host: String,
+    pub pid: u32,
+    pub acquired_at: String,
+    pub refreshed_at: String,
+    /// Held by this app instance.
+    pub owned: bool,
+    /// Holder process is gone or has not saved for hours.
+    pub stale: bool,
+}
+
+/// Filters of the `search_projects` command; empty fields match everything.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectSearchQuery {
+    pub contest: Option<String>,
+    pub judge_name: Option<String>,
+    /// Matches the barème id or name.
+    pub bareme: Option<String>,
+    /// Matches clip display names, file names and authors.
+    pub clip_name: Option<String>,
+    /// Inclusive `YYYY-MM-DD` bounds on the last update.
+    pub updated_from: Option<String>,
+    pub updated_to: Option<String>,
+    /// Folders to search instead of the configured project roots.
+    pub roots: Option<Vec<String>>,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectSearchGroup {
+    pub contest: String,
+    pub projects: Vec<ProjectSummary>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectSnapshot {
+    pub id: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub clip_count: usize,
+    pub scored_count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SnapshotScoreChange {
+    pub clip_id: String,
+    pub clip_name: String,
+    pub criterion_id: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectSnapshotDiff {
+    pub before_updated_at: String,
+    pub after_updated_at: String,
+    pub clips_added: Vec<String>,
+    pub clips_removed: Vec<String>,
+    pub score_changes: Vec<SnapshotScoreChange>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportedProjectBundle {
+    pub project: serde_json::Value,
+    pub file_path: String,
+    pub relinked_clips: usize,
+    pub missing_clips: Vec<String>,
+    pub screenshots: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MissingClip {
+    pub clip_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClipRelinkCandidate {
+    pub file_name: String,
+    pub file_path: String,
+    pub size_bytes: u64,
+    /// 0-100, higher is a safer match.
+    pub score: u8,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClipRelinkProposal {
+    pub clip_id: String,
+    pub file_name: String,
+    pub candidates: Vec<ClipRelinkCandidate>,
+}
+
+/// How judge scores are combined into one clip score.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AggregationMethod {
+    #[default]
+    Mean,
+    Median,
+    /// Mean without the best and worst judge; a plain mean under three judges.
+    TrimmedMean,
+    /// Mean of each judge's scores standardised over that judge's own clips.
+    ZScore,
+    /// Mean of each judge's ranking position, 1 being their best clip.
+    RankAverage,
+}
+
+/// One judge's part in an aggregated score.
+#[derive(Debug, Serialize, Clone)]
+pub struct AggregatedJudgeScore {
+    pub judge_key: String,
+    pub judge_name: String,
+    /// Raw points, `None` when the judge left the clip unscored.
+    pub points: Option<f64>,
+    /// What the method combines: the points, the z-score or the rank.
+    pub value: Option<f64>,
+    /// Left out by the trimmed mean.
+    pub dropped: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AggregatedCategoryScore {
+    pub category: String,
+    pub max: f64,
+    pub score: Option<f64>,
+    pub judges: Vec<AggregatedJudgeScore>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AggregatedClip {
+    pub clip_id: String,
+    pub label: String,
+    pub contest_category: Option<String>,
+    /// `None` when no judge scored the clip.
+    pub score: Option<f64>,
+    pub judge_count: usize,
+    pub judges: Vec<AggregatedJudgeScore>,
+    pub categories: Vec<AggregatedCategoryScore>,
+}
+
+/// Spread of one judge's totals, the basis of the z-score method.
+#[derive(Debug, Serialize, Clone)]
+pub struct AggregationJudge {
+    pub key: String,
+    pub name: String,
+    pub scored_clips: usize,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AggregatedResults {
+    pub method: AggregationMethod,
+    /// `false` for rank averaging, where 1 is the best score.
+    pub higher_is_better: bool,
+    pub total_points: f64,
+    pub judges: Vec<AggregationJudge>,
+    /// In project clip order.
+    pub clips: Vec<AggregatedClip>,
+}
+
+/// Rule deciding between clips with the same aggregated score.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TieBreaker {
+    /// Better aggregated score in the named barème category, e.g. MONTAGE.
+    #[serde(rename_all = "camelCase")]
+    CategoryScore { category: String },
+    /// More judges placing the clip first among the tied clips.
+    FirstPlaceVotes,
+    /// Lower standard deviation of the judges' totals.
+    LowerSpread,
+    /// Organizer's call: clip ids, first listed wins; unlisted clips stay tied.
+    #[serde(rename_all = "camelCase")]
+    OrganizerDecision { clip_ids: Vec<String> },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RankedClip {
+    /// Competition rank: clips still tied share it and the next rank is skipped.
+    pub rank: u32,
+    pub clip_id: String,
+    pub label: String,
+    pub contest_category: Option<String>,
+    pub score: Option<f64>,
+    /// Tied with another clip after every tie-breaker.
+    pub tied: bool,
+}
+
+/// How one group of tied clips was split, or that it could not be.
+#[derive(Debug, Serialize, Clone)]
+pub struct TieResolution {
+    /// Rank the group competed for.
+    pub rank: u32,
+    pub score: Option<f64>,
+    /// Clips of the group in their final order.
+    pub clip_ids: Vec<String>,
+    /// Rule that split the group; `None` when no rule could.
+    pub tie_breaker: Option<TieBreaker>,
+    /// The rule's value per clip, aligned with `clip_ids`.
+    pub values: Vec<Option<f64>>,
+    pub resolved: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RankingResult {
+    pub method: AggregationMethod,
+    pub higher_is_better: bool,
+    pub tie_breakers: Vec<TieBreaker>,
+    pub clips: Vec<RankedClip>,
+    pub ties: Vec<TieResolution>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeSpread {
+    pub key: String,
+    pub name: String,
+    pub scored_clips: usize,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Mean gap to the other judges on the same clips; large values point to
+    /// a judge scoring on a different scale.
+    pub mean_offset: Option<f64>,
+}
+
+/// Rank agreement of two judges over the clips both scored.
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgePairAgreement {
+    pub first_key: String,
+    pub first_name: String,
+    pub second_key: String,
+    pub second_name: String,
+    pub common_clips: usize,
+    pub spearman: Option<f64>,
+    pub kendall: Option<f64>,
+}
+
+/// ICC(2,1) of one criterion over the clips every counted judge scored.
+#[derive(Debug, Serialize, Clone)]
+pub struct CriterionAgreement {
+    pub criterion_id: String,
+    pub name: String,
+    pub clips: usize,
+    /// Judges counted; those with too few scores are left out.
+    pub judges: usize,
+    pub icc: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeOutlier {
+    pub clip_id: String,
+    pub label: String,
+    pub judge_key: String,
+    pub judge_name: String,
+    pub points: f64,
+    pub others_mean: f64,
+    /// `points - others_mean`.
+    pub deviation: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JudgeStatistics {
+    /// Points from the other judges' mean beyond which a total is flagged.
+    pub threshold: f64,
+    pub judges: Vec<JudgeSpread>,
+    pub pairs: Vec<JudgePairAgreement>,
+    pub criteria: Vec<CriterionAgreement>,
+    /// ICC(2,1) of the totals.
+    pub total_icc: Option<f64>,
+    pub outliers: Vec<JudgeOutlier>,
+}
+
+/// Number of entries and hash of the last one, as recorded in the project.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditHead {
+    pub entries: u64,
+    pub hash: String,
+}
+
+/// One criterion score change, chained to the previous entry by `prev_hash`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: String,
+    pub judge_name: String,
+    pub clip_id: String,
+    pub clip_name: String,
+    pub criterion_id: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Empty filters match every entry.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditQuery {
+    pub clip_id: Option<String>,
+    pub criterion_id: Option<String>,
+    pub judge_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub entries: u64,
+    /// First entry that breaks the chain or disagrees with the project.
+    pub broken_at: Option<u64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+    pub verification: AuditVerification,
 }
@@ -0,0 +1,116 @@
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Formula, Workbook};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::json_io;
+use super::results::ResultsModel;
+use super::results_workbook::{self, CellStyle, CellValue, Labels, ResultSheet};
+
+const HEADER_COLOR: u32 = 0x1f2937;
+const EVEN_ROW_COLOR: u32 = 0xf3f4f6;
+const BORDER_COLOR: u32 = 0xd1d5db;
+
+fn xlsx_error(error: rust_xlsxwriter::XlsxError) -> String {
+    format!("Failed to build workbook: {}", error)
+}
+
+fn format_for(style: &CellStyle) -> Format {
+    let base = Format::new()
+        .set_border(FormatBorder::Thin)
+        .set_border_color(Color::RGB(BORDER_COLOR));
+    let header = base
+        .clone()
+        .set_bold()
+        .set_text_wrap()
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_font_color(Color::White)
+        .set_background_color(Color::RGB(HEADER_COLOR));
+    match style {
+        CellStyle::Title => header.set_font_size(13),
+        CellStyle::Header => header,
+        CellStyle::Category(color) => header
+            .set_font_color(Color::Black)
+            .set_background_color(Color::from(color.as_str())),
+        CellStyle::Name => base.set_bold(),
+        CellStyle::Score { even: false } => base.set_align(FormatAlign::Center),
+        CellStyle::Score { even: true } => base
+            .set_align(FormatAlign::Center)
+            .set_background_color(Color::RGB(EVEN_ROW_COLOR)),
+        CellStyle::Total => base.set_bold().set_align(FormatAlign::Center),
+    }
+}
+
+fn write_sheet(workbook: &mut Workbook, sheet: &ResultSheet) -> Result<(), String> {
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(&sheet.name).map_err(xlsx_error)?;
+    // Unscored cells stay blank rather than showing a cached 0.
+    worksheet.set_formula_result_default("");
+
+    for cell in &sheet.cells {
+        let format = format_for(&cell.style);
+        if let Some(merge) = sheet.merge_at(cell.row, cell.column) {
+            let text = match &cell.value {
+                CellValue::Text(text) => text.as_str(),
+                _ => "",
+            };
+            worksheet
+                .merge_range(
+                    merge.first_row,
+                    merge.first_column,
+                    merge.last_row,
+                    merge.last_column,
+                    text,
+                    &format,
+                )
+                .map_err(xlsx_error)?;
+            continue;
+        }
+        match &cell.value {
+            CellValue::Text(text) => {
+                worksheet.write_string_with_format(cell.row, cell.column, text, &format)
+            }
+            CellValue::Number(value) => {
+                worksheet.write_number_with_format(cell.row, cell.column, *value, &format)
+            }
+            CellValue::Blank => worksheet.write_blank(cell.row, cell.column, &format),
+            CellValue::Formula { formula, cached } => {
+                let formula = Formula::new(formula)
+                    .set_result(cached.map(|value| value.to_string()).unwrap_or_default());
+                worksheet.write_formula_with_format(cell.row, cell.column, formula, &format)
+            }
+        }
+        .map_err(xlsx_error)?;
+    }
+
+    for (column, width) in &sheet.column_widths {
+        worksheet
+            .set_column_width(*column, *width)
+            .map_err(xlsx_error)?;
+    }
+    if let Some((row, column)) = sheet.freeze {
+        worksheet
+            .set_freeze_panes(row, column)
+            .map_err(xlsx_error)?;
+    }
+    Ok(())
+}
+
+pub fn build_workbook(sheets: &[ResultSheet]) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    for sheet in sheets {
+        write_sheet(&mut workbook, sheet)?;
+    }
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+pub fn export_results_xlsx(
+    data: serde_json::Value,
+    file_path: &str,
+    labels: HashMap<String, String>,
+) -> Result<(), String> {
+    let model = ResultsModel::from_project(data)?;
+    let sheets = results_workbook::build_sheets(&model, &Labels(labels));
+    let bytes = build_workbook(&sheets)?;
+    json_io::write_atomic(Path::new(file_path), &bytes, "export results workbook")
+}
@@ -2,6 +2,8 @@ use serde::Serialize;
 use std::path::Path;
 use walkdir::WalkDir;
 
+use crate::project::manager::blind;
+
 const VIDEO_EXTENSIONS: &[&str] = &[
     "mp4", "mkv", "avi", "mov", "webm", "flv", "m4v", "wmv", "mpg", "mpeg", "ts", "vob", "ogv",
     "amv",
@@ -22,9 +24,18 @@ fn is_video_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// In the clips folder of a blind project, videos come back under their
+/// entry code instead of their file name.
 #[tauri::command]
 pub fn scan_video_folder(folder_path: String) -> Result<Vec<VideoMetadata>, String> {
-    let path = Path::new(&folder_path);
+    let videos = scan_videos(&folder_path, 1)?;
+    Ok(blind::redact_scan(&folder_path, videos))
+}
+
+/// Lists the videos under `folder_path`, descending at most `max_depth` levels
+/// (1 = the folder itself only).
+pub fn scan_videos(folder_path: &str, max_depth: usize) -> Result<Vec<VideoMetadata>, String> {
+    let path = Path::new(folder_path);
     if !path.exists() || !path.is_dir() {
         return Err(format!("Folder not found: {}", folder_path));
     }
@@ -32,7 +43,7 @@ pub fn scan_video_folder(folder_path: String) -> Result<Vec<VideoMetadata>, Stri
     let mut videos = Vec::new();
 
     for entry in WalkDir::new(path)
-        .max_depth(1)
+        .max_depth(max_depth)
         .into_iter()
         .filter_map(|e| e.ok())
     {
@@ -21,6 +21,7 @@ import { useUIStore } from '@/store/useUIStore'
 import { usePlayerStore } from '@/store/usePlayerStore'
 import { useAppUpdateStore } from '@/store/useAppUpdateStore'
 import { useAutoSave } from '@/hooks/useAutoSave'
+import { useExternalProjectChanges } from '@/hooks/useExternalProjectChanges'
 import { useKeyboardShortcuts } from '@/hooks/useKeyboardShortcuts'
 import { usePlayer } from '@/hooks/usePlayer'
 import { useSaveProject } from '@/hooks/useSaveProject'
@@ -117,6 +118,7 @@ export default function AppLayout() {
   }, [])
 
   useAutoSave()
+  useExternalProjectChanges()
 
   useAutoDetachNotesWindow({
     hasProject: Boolean(currentProject),
@@ -1,11 +1,12 @@
 import { useState, useEffect, useCallback } from 'react'
-import { FilePlus, FolderOpen, Folder, Trash2 } from 'lucide-react'
+import { AlertTriangle, FilePlus, FolderOpen, Folder, Trash2 } from 'lucide-react'
 import { useUIStore } from '@/store/useUIStore'
 import * as tauri from '@/services/tauri'
 import { forgetRecentProjectPath, hideProjectPath, listHiddenProjectPaths, listRecentProjectPaths, rememberRecentProjectPath, setRecentProjectPaths } from '@/services/recentProjects'
 import { loadAndApplyProjectFile } from '@/services/projectSession'
 import { HoverTextTooltip } from '@/components/ui/HoverTextTooltip'
 import { ProjectDeletionConfirmDialog } from '@/components/project/ProjectDeletionConfirmDialog'
+import { useConfirmLockTakeOver } from '@/components/project/useConfirmLockTakeOver'
 import { useI18n } from '@/i18n'
 
 interface ProjectListItem {
@@ -13,6 +14,8 @@ interface ProjectListItem {
   judgeName: string
   updatedAt: string
   filePath: string
+  /** Set when the file could not be read as a project. */
+  parseError?: string | null
 }
 
 interface WelcomeScreenState {
@@ -27,6 +30,7 @@ function mapSummaryToProjectListItem(project: tauri.ProjectSummary): ProjectList
     judgeName: project.judge_name,
     updatedAt: project.updated_at,
     filePath: project.file_path,
+    parseError: project.parse_error,
   }
 }
 
@@ -39,6 +43,7 @@ export function WelcomeScreen() {
   const { setShowProjectModal, setProjectsFolderPath } = useUIStore()
   const configuredProjectsFolderPath = useUIStore((state) => state.projectsFolderPath)
   const { t, formatDate } = useI18n()
+  const confirmTakeOver = useConfirmLockTakeOver()
   const [{ projects, folderPath, loading }, setScreenState] = useState<WelcomeScreenState>({
     projects: [],
     folderPath: '',
@@ -85,7 +90,8 @@ export function WelcomeScreen() {
         const loadedRecent = await Promise.all(
           missingPaths.map(async (path) => {
             try {
-              const data = await tauri.loadProjectFile(path) as {
+              // Read-only: listing a project must not lock it.
+              const data = await tauri.loadProjectFile(path, { readOnly: true }) as {
                 project?: {
                   name?: string
                   judgeName?: string
@@ -129,13 +135,13 @@ export function WelcomeScreen() {
 
   const openProjectFromFile = useCallback(async (filePath: string) => {
     try {
-      await loadAndApplyProjectFile(filePath)
+      await loadAndApplyProjectFile(filePath, { confirmTakeOver })
       await rememberRecentProjectPath(filePath)
     } catch (error) {
       console.error('Failed to open project:', error)
       alert(t("Impossible d'ouvrir ce projet. Il a peut-être été déplacé ou supprimé."))
     }
-  }, [t])
+  }, [confirmTakeOver, t])
 
   const handleOpenProject = async () => {
     try {
@@ -249,20 +255,29 @@ export function WelcomeScreen() {
                   >
                     <div className="min-w-0 flex-1">
                       <div className="text-sm font-medium text-white truncate">{project.name}</div>
-                      <div className="text-[10px] text-gray-500 truncate">
-                        {project.judgeName && (
-                          <span className="text-gray-400">
-                            {project.judgeName} —{' '}
-                          </span>
-                        )}
-                        {formatDate(project.updatedAt, {
-                          day: 'numeric',
-                          month: 'short',
-                          year: 'numeric',
-                          hour: '2-digit',
-                          minute: '2-digit',
-                        })}
-                      </div>
+                      {project.parseError ? (
+                        <HoverTextTooltip text={project.parseError}>
+                          <div className="flex items-center gap-1 text-[10px] text-red-400 truncate">
+                            <AlertTriangle size={10} className="shrink-0" />
+                            {t('Fichier illisible')}
+                          </div>
+                        </HoverTextTooltip>
+                      ) : (
+                        <div className="text-[10px] text-gray-500 truncate">
+                          {project.judgeName && (
+                            <span className="text-gray-400">
+                              {project.judgeName} —{' '}
+                            </span>
+                          )}
+                          {formatDate(project.updatedAt, {
+                            day: 'numeric',
+                            month: 'short',
+                            year: 'numeric',
+                            hour: '2-digit',
+                            minute: '2-digit',
+                          })}
+                        </div>
+                      )}
                     </div>
                   </button>
                   <button
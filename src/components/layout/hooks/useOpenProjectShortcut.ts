@@ -2,16 +2,18 @@ import { useCallback } from 'react'
 import * as tauri from '@/services/tauri'
 import { rememberRecentProjectPath } from '@/services/recentProjects'
 import { loadAndApplyProjectFile } from '@/services/projectSession'
+import { useConfirmLockTakeOver } from '@/components/project/useConfirmLockTakeOver'
 
 export function useOpenProjectShortcut() {
+  const confirmTakeOver = useConfirmLockTakeOver()
   return useCallback(async () => {
     try {
       const filePath = await tauri.openProjectDialog()
       if (!filePath) return
-      await loadAndApplyProjectFile(filePath)
+      await loadAndApplyProjectFile(filePath, { confirmTakeOver })
       await rememberRecentProjectPath(filePath)
     } catch (error) {
       console.error('Failed to open project:', error)
     }
-  }, [])
+  }, [confirmTakeOver])
 }
@@ -4,6 +4,7 @@ import { useMediaInfoData } from '@/components/player/mediaInfo/useMediaInfoData
 import { buildMediaInfoSections } from '@/components/player/mediaInfo/mediaInfoSections'
 import { MediaInfoSectionTable } from '@/components/player/mediaInfo/MediaInfoSectionTable'
 import { HoverTextTooltip } from '@/components/ui/HoverTextTooltip'
+import { clipMediaUrl } from '@/services/tauri'
 import { useI18n } from '@/i18n'
 
 interface MediaInfoPanelProps {
@@ -88,6 +89,13 @@ export default function MediaInfoPanel({ clipName, filePath, onClose }: MediaInf
         </div>
 
         <div className="px-4 py-3 overflow-y-auto max-h-[68vh]">
+          {/* First frame only; the mpv player does the actual playback. */}
+          <video
+            src={clipMediaUrl(filePath)}
+            preload="metadata"
+            muted
+            className="mb-3 w-full max-h-40 rounded-md bg-black"
+          />
           {error ? (
             <p className="text-red-400 text-sm">{error}</p>
           ) : !info ? (
@@ -0,0 +1,14 @@
+import { useCallback } from 'react'
+import { useI18n } from '@/i18n'
+import type { ProjectLockInfo } from '@/services/tauri'
+
+/** Asks before taking over a project lock left by an instance that is gone. */
+export function useConfirmLockTakeOver() {
+  const { t } = useI18n()
+  return useCallback((lock: ProjectLockInfo) => confirm(
+    t('Ce projet est resté verrouillé par {judge} sur {host}, qui ne l\'utilise plus. Reprendre le verrou ?', {
+      judge: lock.judge_name.trim() || t('un autre juge'),
+      host: lock.host,
+    }),
+  ), [t])
+}
@@ -84,7 +84,7 @@ export function useJudgeImport() {
 
       for (const path of jsonPaths) {
         try {
-          const payload = await tauri.loadProjectFile(path)
+          const payload = await tauri.loadProjectFile(path, { readOnly: true })
           const normalized = normalizeImportedJudge(payload, clips)
 
           if (!normalized) {
@@ -6,9 +6,11 @@ import { rememberRecentProjectPath } from '@/services/recentProjects'
 import { loadAndApplyProjectFile } from '@/services/projectSession'
 import { resolveProjectBareme } from '@/store/projectStoreProjectActions'
 import { useI18n } from '@/i18n'
+import { useConfirmLockTakeOver } from '@/components/project/useConfirmLockTakeOver'
 
 export function useProjectFileActions() {
   const { t } = useI18n()
+  const confirmTakeOver = useConfirmLockTakeOver()
   const {
     currentProject,
     setFilePath,
@@ -27,7 +29,7 @@ export function useProjectFileActions() {
       const filePath = await tauri.openProjectDialog()
       if (!filePath) return
 
-      await loadAndApplyProjectFile(filePath)
+      await loadAndApplyProjectFile(filePath, { confirmTakeOver })
       await rememberRecentProjectPath(filePath)
     } catch (errorValue) {
       console.error('Failed to open project:', errorValue)
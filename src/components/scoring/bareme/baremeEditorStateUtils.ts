@@ -221,6 +221,8 @@ export function buildBaremeFromForm({
       category: criterion.category?.trim() || undefined,
     })),
     categoryColors,
+    // Not edited here; kept so a save does not drop them.
+    tieBreakers: editingBareme?.tieBreakers,
     totalPoints: getTotalPoints(normalizedCriteria),
     createdAt: editingBareme?.createdAt || now,
     updatedAt: now,
@@ -29,7 +29,7 @@ export function useBaremeJsonTransfer({
     try {
       const filePath = await tauri.openJsonDialog()
       if (!filePath) return
-      const data = await tauri.loadProjectFile(filePath)
+      const data = await tauri.loadProjectFile(filePath, { readOnly: true })
       const importedBaremes = importFromJsonData(data, availableBaremes)
       if (importedBaremes.length === 0) {
         alert(t('Aucun barème valide trouvé dans ce fichier JSON.'))
@@ -58,7 +58,7 @@ export function useBaremeJsonTransfer({
 
           for (const filePath of jsonPaths) {
             try {
-              const data = await tauri.loadProjectFile(filePath)
+              const data = await tauri.loadProjectFile(filePath, { readOnly: true })
               const importedBaremes = importFromJsonData(data, workingBaremes)
               importedCount += importedBaremes.length
               if (importedBaremes.length > 0) {
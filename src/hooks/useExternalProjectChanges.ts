@@ -0,0 +1,36 @@
+import { useEffect } from 'react'
+import { useI18n } from '@/i18n'
+import * as tauri from '@/services/tauri'
+import { loadAndApplyProjectFile } from '@/services/projectSession'
+import { useProjectStore } from '@/store/useProjectStore'
+
+// Only the open project is watched, so any project event is about it. Asking
+// before the next save keeps an edit made elsewhere from being overwritten.
+export function useExternalProjectChanges() {
+  const { t } = useI18n()
+
+  useEffect(() => {
+    let disposed = false
+    let unlisten: (() => void) | null = null
+
+    tauri.listenExternalFileChanges((change) => {
+      if (change.scope !== 'project' || (change.kind !== 'modified' && change.kind !== 'created')) return
+      const filePath = useProjectStore.getState().currentProject?.filePath
+      if (!filePath) return
+      if (!confirm(t('Le projet a été modifié en dehors de l\'application. Recharger le fichier ?'))) return
+      loadAndApplyProjectFile(filePath).catch((error) => {
+        console.error('Failed to reload project:', error)
+      })
+    }).then((stop) => {
+      if (disposed) stop()
+      else unlisten = stop
+    }).catch((error) => {
+      console.error('Failed to listen for external file changes:', error)
+    })
+
+    return () => {
+      disposed = true
+      unlisten?.()
+    }
+  }, [t])
+}
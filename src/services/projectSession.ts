@@ -5,6 +5,7 @@ import { useProjectStore } from '@/store/useProjectStore'
 import { OFFICIAL_BAREME } from '@/types/bareme'
 import type { Bareme } from '@/types/bareme'
 import type { Clip, ImportedJudgeData, NoteData, Project, ProjectData } from '@/types/project'
+import type { ProjectLockInfo } from '@/services/tauri'
 
 interface LoadedProjectFile {
   version?: string
@@ -18,8 +19,30 @@ interface LoadedProjectFile {
   importedJudges?: ImportedJudgeData[]
 }
 
-export async function loadAndApplyProjectFile(filePath: string): Promise<void> {
-  const rawData = (await tauri.loadProjectFile(filePath)) as LoadedProjectFile
+export interface LoadProjectFileOptions {
+  /** Asked when the file is locked by an instance that is gone; `true` takes the lock over. */
+  confirmTakeOver?: (lock: ProjectLockInfo) => boolean | Promise<boolean>
+}
+
+async function loadProjectFileWithTakeOver(
+  filePath: string,
+  options: LoadProjectFileOptions,
+): Promise<LoadedProjectFile> {
+  try {
+    return (await tauri.loadProjectFile(filePath)) as LoadedProjectFile
+  } catch (error) {
+    const lock = await tauri.getProjectLock(filePath).catch(() => null)
+    if (!lock || lock.owned || !lock.stale || !options.confirmTakeOver) throw error
+    if (!(await options.confirmTakeOver(lock))) throw error
+    return (await tauri.loadProjectFile(filePath, { takeOver: true })) as LoadedProjectFile
+  }
+}
+
+export async function loadAndApplyProjectFile(
+  filePath: string,
+  options: LoadProjectFileOptions = {},
+): Promise<void> {
+  const rawData = await loadProjectFileWithTakeOver(filePath, options)
   const data = rawData.projectData && typeof rawData.projectData === 'object'
     ? rawData.projectData
     : rawData
@@ -40,6 +63,14 @@ export async function loadAndApplyProjectFile(filePath: string): Promise<void> {
     importedJudges: Array.isArray(data.importedJudges) ? data.importedJudges : [],
   }
 
+  // Opening the project must not fail because its videos are elsewhere.
+  await tauri.openBlindSession(filePath, rawData).catch((error) => {
+    console.error('Failed to open blind session:', error)
+  })
+  await tauri.watchProjectFiles(filePath).catch((error) => {
+    console.error('Failed to watch project files:', error)
+  })
+
   useProjectStore.getState().setProjectFromData(normalizedData)
 
   const notationStore = useNotationStore.getState()
@@ -1,11 +1,81 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import type { TieBreaker } from '@/types/bareme'
+import type { BaremeRevisionPin, ClipOrderSettings, ImportedJudgeData } from '@/types/project'
 
-export async function saveProjectFile(data: unknown, filePath: string): Promise<void> {
-  await invoke('save_project', { data, filePath })
+/** `reason` is recorded in the audit log with the score changes of this save. */
+export async function saveProjectFile(
+  data: unknown,
+  filePath: string,
+  reason?: string,
+): Promise<void> {
+  await invoke('save_project', { data, filePath, reason: reason ?? null })
 }
 
-export async function loadProjectFile(filePath: string): Promise<unknown> {
-  return await invoke('load_project', { filePath })
+export interface LoadProjectOptions {
+  /** Replace a stale lock left by another instance, e.g. after a crash. */
+  takeOver?: boolean
+  /** Read without locking, for imports that only pick data out of the file. */
+  readOnly?: boolean
+}
+
+export async function loadProjectFile(
+  filePath: string,
+  options: LoadProjectOptions = {},
+): Promise<unknown> {
+  return await invoke('load_project', { filePath, ...options })
+}
+
+export interface ProjectLockInfo {
+  judge_name: string
+  host: string
+  pid: number
+  acquired_at: string
+  refreshed_at: string
+  owned: boolean
+  stale: boolean
+}
+
+export async function getProjectLock(filePath: string): Promise<ProjectLockInfo | null> {
+  return await invoke('get_project_lock', { filePath })
+}
+
+export async function releaseProjectLock(filePath: string): Promise<void> {
+  await invoke('release_project_lock', { filePath })
+}
+
+export interface ExternalFileChange {
+  scope: 'project' | 'bareme'
+  kind: 'modified' | 'created' | 'deleted' | 'renamed'
+  path: string
+  new_path: string | null
+  old_modified: string | null
+  new_modified: string | null
+  old_hash: string | null
+  new_hash: string | null
+}
+
+export async function watchProjectFiles(filePath: string | null): Promise<void> {
+  await invoke('watch_project_files', { filePath })
+}
+
+export async function unwatchProjectFiles(): Promise<void> {
+  await invoke('unwatch_project_files')
+}
+
+export async function listenExternalFileChanges(
+  onChange: (change: ExternalFileChange) => void,
+): Promise<UnlistenFn> {
+  const unlistenProject = await listen<ExternalFileChange>('project:external-change', (event) => {
+    onChange(event.payload)
+  })
+  const unlistenBaremes = await listen<ExternalFileChange>('baremes:external-change', (event) => {
+    onChange(event.payload)
+  })
+  return () => {
+    unlistenProject()
+    unlistenBaremes()
+  }
 }
 
 export async function exportJsonFile(data: unknown, filePath: string): Promise<void> {
@@ -21,6 +91,31 @@ export interface ProjectSummary {
   judge_name: string
   updated_at: string
   file_path: string
+  clip_count: number
+  scored_count: number
+  completion_percent: number
+  bareme_id: string
+  bareme_name: string
+  imported_judge_count: number
+  size_bytes: number
+  contest: string
+  parse_error: string | null
+}
+
+export interface ProjectSearchQuery {
+  contest?: string
+  judgeName?: string
+  bareme?: string
+  clipName?: string
+  updatedFrom?: string
+  updatedTo?: string
+  roots?: string[]
+  maxDepth?: number
+}
+
+export interface ProjectSearchGroup {
+  contest: string
+  projects: ProjectSummary[]
 }
 
 export async function getDefaultProjectsFolder(): Promise<string> {
@@ -31,8 +126,15 @@ export async function getDefaultBaremesFolder(): Promise<string> {
   return await invoke('get_default_baremes_folder')
 }
 
-export async function listProjectsInFolder(folderPath: string): Promise<ProjectSummary[]> {
-  return await invoke('list_projects_in_folder', { folderPath })
+export async function listProjectsInFolder(
+  folderPath: string,
+  maxDepth?: number,
+): Promise<ProjectSummary[]> {
+  return await invoke('list_projects_in_folder', { folderPath, maxDepth })
+}
+
+export async function searchProjects(query: ProjectSearchQuery): Promise<ProjectSearchGroup[]> {
+  return await invoke('search_projects', { query })
 }
 
 export async function ensureDirectoryExists(path: string): Promise<void> {
@@ -43,6 +145,510 @@ export async function saveBareme(data: unknown, baremeId: string): Promise<void>
   await invoke('save_bareme', { data, baremeId })
 }
 
+export interface BaremeValidationIssue {
+  path: string
+  criterion_id: string | null
+  message: string
+}
+
+export async function validateBareme(data: unknown): Promise<BaremeValidationIssue[]> {
+  return await invoke('validate_bareme', { data })
+}
+
+export interface ImportedSheetBareme {
+  bareme: unknown
+  /** Id of the stored barème returned instead when the grid already existed. */
+  duplicate_of: string | null
+  judge_name: string | null
+  warnings: string[]
+}
+
+export async function importBaremeFromSheet(
+  filePath: string,
+  name?: string,
+): Promise<ImportedSheetBareme> {
+  return await invoke('import_bareme_from_sheet', { filePath, name: name ?? null })
+}
+
+export interface JudgeSheetClipCandidate {
+  clip_id: string
+  display_name: string
+  author: string | null
+  score: number
+}
+
+export interface JudgeSheetRow {
+  row: number
+  label: string
+  clip_id: string | null
+  candidates: JudgeSheetClipCandidate[]
+}
+
+export interface JudgeSheetColumn {
+  column_name: string
+  sheet_max: number
+  criterion_id: string | null
+  criterion_name: string | null
+}
+
+export interface JudgeSheetValueIssue {
+  row: number
+  label: string
+  criterion_id: string
+  value: string
+  message: string
+}
+
+export interface JudgeSheetImport {
+  judge: ImportedJudgeData | null
+  judge_name: string
+  columns: JudgeSheetColumn[]
+  matched: JudgeSheetRow[]
+  unmatched: JudgeSheetRow[]
+  ambiguous: JudgeSheetRow[]
+  value_issues: JudgeSheetValueIssue[]
+}
+
+/**
+ * Previews another judge's score sheet against the project. `rowMatches`
+ * (sheet row -> clip id) settles rows reported as ambiguous or unmatched.
+ */
+export async function importJudgeSheet(
+  filePath: string,
+  data: unknown,
+  options: { judgeName?: string; rowMatches?: Record<number, string> } = {},
+): Promise<JudgeSheetImport> {
+  return await invoke('import_judge_sheet', {
+    filePath,
+    data,
+    judgeName: options.judgeName ?? null,
+    rowMatches: options.rowMatches ?? null,
+  })
+}
+
+export interface MergedJudge {
+  source: string
+  judge_name: string
+  matched_clips: number
+  unmatched_clips: string[]
+}
+
+export interface DuplicateJudgeName {
+  judge_name: string
+  sources: string[]
+  renamed_to: string[]
+}
+
+export interface BaremeMismatch {
+  source: string
+  judge_name: string
+  bareme_id: string
+  content_hash: string
+  expected_bareme_id: string
+  expected_content_hash: string
+}
+
+export interface PartialClip {
+  clip_id: string
+  label: string
+  scored_by: string[]
+  missing: string[]
+}
+
+export interface JudgeMergeConflicts {
+  duplicate_judges: DuplicateJudgeName[]
+  bareme_mismatches: BaremeMismatch[]
+  partial_clips: PartialClip[]
+}
+
+export interface JudgeMerge {
+  project: unknown
+  judges: MergedJudge[]
+  conflicts: JudgeMergeConflicts
+}
+
+/** Adds judge project files to the project's imported judges, without saving. */
+export async function mergeJudgeProjects(
+  data: unknown,
+  filePaths: string[],
+): Promise<JudgeMerge> {
+  return await invoke('merge_judge_projects', { data, filePaths })
+}
+
+/**
+ * Builds one results project from every judge's project file, the first one
+ * giving the clips and barème. Nothing is saved.
+ */
+export async function mergeJudgeFiles(
+  filePaths: string[],
+  name?: string,
+): Promise<JudgeMerge> {
+  return await invoke('merge_judge_files', { filePaths, name: name ?? null })
+}
+
+export interface BlindIdentity {
+  code: string
+  clip_id: string
+  file_name: string
+  file_path: string
+  display_name: string
+  author: string | null
+}
+
+export interface BlindSessionStatus {
+  matched: string[]
+  missing: string[]
+}
+
+/**
+ * Switches the project to blind judging. Returns the redacted project; only
+ * `organizerKey` can reveal or restore the clips' names.
+ */
+export async function enableBlindMode(data: unknown, organizerKey: string): Promise<unknown> {
+  return await invoke('enable_blind_mode', { data, organizerKey })
+}
+
+/** Finds the videos of the blind project opened from `filePath` in its clips folder. */
+export async function openBlindSession(filePath: string, data: unknown): Promise<BlindSessionStatus> {
+  return await invoke('open_blind_session', { filePath, data })
+}
+
+export async function closeBlindSession(filePath: string): Promise<void> {
+  await invoke('close_blind_session', { filePath })
+}
+
+export async function revealBlindIdentities(
+  data: unknown,
+  organizerKey: string,
+): Promise<BlindIdentity[]> {
+  return await invoke('reveal_blind_identities', { data, organizerKey })
+}
+
+/** Restores the clips' names and leaves blind mode. */
+export async function disableBlindMode(data: unknown, organizerKey: string): Promise<unknown> {
+  return await invoke('disable_blind_mode', { data, organizerKey })
+}
+
+export interface JudgeClipOrder {
+  judge_name: string
+  clip_ids: string[]
+}
+
+export interface ClipOrderPlan {
+  settings: ClipOrderSettings
+  orders: JudgeClipOrder[]
+}
+
+/** Each judge's clip order, from `settings` or the ones stored in the project. */
+export async function planClipOrder(
+  data: unknown,
+  settings?: ClipOrderSettings,
+): Promise<ClipOrderPlan> {
+  return await invoke('plan_clip_order', { data, settings: settings ?? null })
+}
+
+/** Sorts the clips in the judge's drawn order and stores the seed in the project. */
+export async function applyClipOrder(
+  data: unknown,
+  settings?: ClipOrderSettings,
+  judgeName?: string,
+): Promise<unknown> {
+  return await invoke('apply_clip_order', {
+    data,
+    settings: settings ?? null,
+    judgeName: judgeName ?? null,
+  })
+}
+
+export interface AuditEntry {
+  seq: number
+  timestamp: string
+  judge_name: string
+  clip_id: string
+  clip_name: string
+  criterion_id: string
+  old_value: number | string | boolean | null
+  new_value: number | string | boolean | null
+  reason?: string
+  prev_hash: string
+  hash: string
+}
+
+export interface AuditVerification {
+  valid: boolean
+  entries: number
+  broken_at: number | null
+  message: string | null
+}
+
+export interface AuditLog {
+  entries: AuditEntry[]
+  verification: AuditVerification
+}
+
+/** Empty filters match every entry. */
+export interface AuditQuery {
+  clipId?: string
+  criterionId?: string
+  judgeName?: string
+}
+
+/** Score changes recorded for the project file, with the check of its hash chain. */
+export async function queryAuditLog(filePath: string, query?: AuditQuery): Promise<AuditLog> {
+  return await invoke('query_audit_log', { filePath, query: query ?? null })
+}
+
+export async function verifyAuditLog(filePath: string): Promise<AuditVerification> {
+  return await invoke('verify_audit_log', { filePath })
+}
+
+export async function exportAuditLogCsv(
+  filePath: string,
+  outputPath: string,
+  query?: AuditQuery,
+): Promise<void> {
+  await invoke('export_audit_log_csv', { filePath, outputPath, query: query ?? null })
+}
+
+export async function exportResultsXlsx(
+  data: unknown,
+  filePath: string,
+  labels?: Record<string, string>,
+): Promise<void> {
+  await invoke('export_results_xlsx', { data, filePath, labels: labels ?? null })
+}
+
+export async function exportResultsOds(
+  data: unknown,
+  filePath: string,
+  labels?: Record<string, string>,
+): Promise<void> {
+  await invoke('export_results_ods', { data, filePath, labels: labels ?? null })
+}
+
+export async function exportResultsCsv(
+  data: unknown,
+  filePath: string,
+  labels?: Record<string, string>,
+): Promise<void> {
+  await invoke('export_results_csv', { data, filePath, labels: labels ?? null })
+}
+
+export type AggregationMethod = 'mean' | 'median' | 'trimmedMean' | 'zScore' | 'rankAverage'
+
+export interface AggregatedJudgeScore {
+  judge_key: string
+  judge_name: string
+  points: number | null
+  value: number | null
+  dropped: boolean
+}
+
+export interface AggregatedCategoryScore {
+  category: string
+  max: number
+  score: number | null
+  judges: AggregatedJudgeScore[]
+}
+
+export interface AggregatedClip {
+  clip_id: string
+  label: string
+  contest_category: string | null
+  score: number | null
+  judge_count: number
+  judges: AggregatedJudgeScore[]
+  categories: AggregatedCategoryScore[]
+}
+
+export interface AggregationJudge {
+  key: string
+  name: string
+  scored_clips: number
+  mean: number | null
+  std_dev: number | null
+}
+
+export interface AggregatedResults {
+  method: AggregationMethod
+  higher_is_better: boolean
+  total_points: number
+  judges: AggregationJudge[]
+  clips: AggregatedClip[]
+}
+
+export async function aggregateResults(
+  data: unknown,
+  method?: AggregationMethod,
+): Promise<AggregatedResults> {
+  return await invoke('aggregate_results', { data, method: method ?? null })
+}
+
+export interface RankedClip {
+  rank: number
+  clip_id: string
+  label: string
+  contest_category: string | null
+  score: number | null
+  tied: boolean
+}
+
+export interface TieResolution {
+  rank: number
+  score: number | null
+  clip_ids: string[]
+  tie_breaker: TieBreaker | null
+  values: (number | null)[]
+  resolved: boolean
+}
+
+export interface RankingResult {
+  method: AggregationMethod
+  higher_is_better: boolean
+  tie_breakers: TieBreaker[]
+  clips: RankedClip[]
+  ties: TieResolution[]
+}
+
+export async function rankResults(
+  data: unknown,
+  options: {
+    method?: AggregationMethod
+    tieBreakers?: TieBreaker[]
+    contestCategory?: string
+  } = {},
+): Promise<RankingResult> {
+  return await invoke('rank_results', {
+    data,
+    method: options.method ?? null,
+    tieBreakers: options.tieBreakers ?? null,
+    contestCategory: options.contestCategory ?? null,
+  })
+}
+
+export interface JudgeSpread {
+  key: string
+  name: string
+  scored_clips: number
+  mean: number | null
+  std_dev: number | null
+  min: number | null
+  max: number | null
+  mean_offset: number | null
+}
+
+export interface JudgePairAgreement {
+  first_key: string
+  first_name: string
+  second_key: string
+  second_name: string
+  common_clips: number
+  spearman: number | null
+  kendall: number | null
+}
+
+export interface CriterionAgreement {
+  criterion_id: string
+  name: string
+  clips: number
+  judges: number
+  icc: number | null
+}
+
+export interface JudgeOutlier {
+  clip_id: string
+  label: string
+  judge_key: string
+  judge_name: string
+  points: number
+  others_mean: number
+  deviation: number
+}
+
+export interface JudgeStatistics {
+  threshold: number
+  judges: JudgeSpread[]
+  pairs: JudgePairAgreement[]
+  criteria: CriterionAgreement[]
+  total_icc: number | null
+  outliers: JudgeOutlier[]
+}
+
+export async function judgeStatistics(
+  data: unknown,
+  threshold?: number,
+): Promise<JudgeStatistics> {
+  return await invoke('judge_statistics', { data, threshold: threshold ?? null })
+}
+
+export interface BaremeRevision {
+  revision: number
+  content_hash: string
+  name: string
+  revised_at: string
+  total_points: number
+  criteria_count: number
+}
+
+export interface BaremeCriterionChange {
+  criterion_id: string
+  name: string
+  previous_name: string | null
+  from_min: number | null
+  from_max: number | null
+  from_step: number | null
+  to_min: number | null
+  to_max: number | null
+  to_step: number | null
+}
+
+export interface BaremeRevisionDiff {
+  bareme_id: string
+  from_revision: number
+  to_revision: number
+  from_total_points: number
+  to_total_points: number
+  added: BaremeCriterionChange[]
+  removed: BaremeCriterionChange[]
+  rescaled: BaremeCriterionChange[]
+  renamed: BaremeCriterionChange[]
+}
+
+export interface BaremeRevisionPinStatus {
+  pinned: BaremeRevisionPin | null
+  current: BaremeRevisionPin | null
+  /** The barème changed since the project was pinned. */
+  mismatch: boolean
+}
+
+export async function checkBaremeRevisionPin(data: unknown): Promise<BaremeRevisionPinStatus> {
+  return await invoke('check_bareme_revision_pin', { data })
+}
+
+export async function repinBaremeRevision(data: unknown): Promise<unknown> {
+  return await invoke('repin_bareme_revision', { data })
+}
+
+export async function listBaremeRevisions(baremeId: string): Promise<BaremeRevision[]> {
+  return await invoke('list_bareme_revisions', { baremeId })
+}
+
+export async function loadBaremeRevision(baremeId: string, revision: number): Promise<unknown> {
+  return await invoke('load_bareme_revision', { baremeId, revision })
+}
+
+export async function diffBaremeRevisions(
+  baremeId: string,
+  fromRevision: number,
+  toRevision?: number,
+): Promise<BaremeRevisionDiff> {
+  return await invoke('diff_bareme_revisions', {
+    baremeId,
+    fromRevision,
+    toRevision: toRevision ?? null,
+  })
+}
+
 export async function deleteBareme(baremeId: string): Promise<void> {
   await invoke('delete_bareme', { baremeId })
 }
@@ -51,6 +657,28 @@ export async function loadBaremes(): Promise<unknown[]> {
   return await invoke('load_baremes')
 }
 
+export interface QuarantinedBareme {
+  id: string
+  original_file_name: string
+  bareme_id: string
+  bareme_name: string
+  reason: string
+  quarantined_at: string
+  size_bytes: number
+}
+
+export async function listQuarantinedBaremes(): Promise<QuarantinedBareme[]> {
+  return await invoke('list_quarantined_baremes')
+}
+
+export async function restoreQuarantinedBareme(entryId: string): Promise<unknown> {
+  return await invoke('restore_quarantined_bareme', { entryId })
+}
+
+export async function purgeQuarantinedBaremes(entryIds?: string[]): Promise<number> {
+  return await invoke('purge_quarantined_baremes', { entryIds: entryIds ?? null })
+}
+
 export async function saveUserSettings(data: unknown): Promise<void> {
   await invoke('save_user_settings', { data })
 }
@@ -1,4 +1,7 @@
-import { invoke } from '@tauri-apps/api/core'
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
+
+/** Clip path of a blind project; the rest is the entry code. */
+const BLIND_ALIAS_PREFIX = 'amv-blind://localhost/'
 
 export interface VideoMetadata {
   file_name: string
@@ -10,3 +13,14 @@ export interface VideoMetadata {
 export async function scanVideoFolder(folderPath: string): Promise<VideoMetadata[]> {
   return await invoke('scan_video_folder', { folderPath })
 }
+
+/**
+ * URL the webview can load a clip from. Blind clips go through the
+ * `amv-blind` protocol, so their real path never reaches the page.
+ */
+export function clipMediaUrl(filePath: string): string {
+  if (filePath.startsWith(BLIND_ALIAS_PREFIX)) {
+    return convertFileSrc(filePath.slice(BLIND_ALIAS_PREFIX.length), 'amv-blind')
+  }
+  return convertFileSrc(filePath)
+}
@@ -1,4 +1,5 @@
 import { create } from 'zustand'
+import * as tauri from '@/services/tauri'
 import type {
   Project,
   Clip,
@@ -269,6 +270,18 @@ export const useProjectStore = create<ProjectStore>((set, get) => ({
   },
 
   reset: () => {
+    const filePath = get().currentProject?.filePath
+    if (filePath) {
+      tauri.releaseProjectLock(filePath).catch((error) => {
+        console.error('Failed to release project lock:', error)
+      })
+      tauri.closeBlindSession(filePath).catch((error) => {
+        console.error('Failed to close blind session:', error)
+      })
+      tauri.unwatchProjectFiles().catch((error) => {
+        console.error('Failed to stop watching project files:', error)
+      })
+    }
     set({
       currentProject: null,
       clips: [],
@@ -20,6 +20,13 @@ export interface Criterion {
   validationRules?: ValidationRule[]
 }
 
+/** Rule deciding between clips left tied by the aggregated score. */
+export type TieBreaker =
+  | { type: 'categoryScore'; category: string }
+  | { type: 'firstPlaceVotes' }
+  | { type: 'lowerSpread' }
+  | { type: 'organizerDecision'; clipIds: string[] }
+
 export interface Bareme {
   id: string
   name: string
@@ -31,6 +38,11 @@ export interface Bareme {
   totalPoints: number
   createdAt: string
   updatedAt: string
+  /** Set by the backend on save; a change to scored content bumps it. */
+  revision?: number
+  contentHash?: string
+  /** Applied in order to break ties in the final ranking. */
+  tieBreakers?: TieBreaker[]
 }
 
 export const OFFICIAL_BAREME: Bareme = {
@@ -1,4 +1,4 @@
-import type { Bareme } from '@/types/bareme'
+import type { Bareme, TieBreaker } from '@/types/bareme'
 
 export interface Project {
   id: string
@@ -28,6 +28,10 @@ export interface Clip {
   favoriteComment?: string
   order: number
   thumbnailTime?: number
+  /** Size, quick hash and modification time of the video, recorded by the backend to relink it. */
+  fileSize?: number
+  contentHash?: string
+  fileModifiedAt?: number | null
 }
 
 export interface ProjectSettings {
@@ -60,6 +64,52 @@ export interface ProjectData {
   clips: Clip[]
   notes: Record<string, NoteData>
   importedJudges?: ImportedJudgeData[]
+  /** Barème revision the scores were given with, set by the backend on save. */
+  baremeRevision?: BaremeRevisionPin
+  /** Overrides the barème's tie-breakers for this contest. */
+  tieBreakers?: TieBreaker[]
+  /** Set while the contest is judged blind; clips only show their entry code. */
+  blind?: BlindMode
+  /** How the judges' clip orders are drawn, kept to reproduce them. */
+  clipOrder?: ClipOrderSettings
+  /** Last audit log entry when the project was saved, set by the backend. */
+  auditHead?: AuditHead
+}
+
+export interface AuditHead {
+  entries: number
+  hash: string
+}
+
+export type ClipOrderMethod = 'shuffle' | 'latinSquare'
+
+export interface ClipOrderSettings {
+  method: ClipOrderMethod
+  /** Any text; the backend draws one when empty. */
+  seed: string
+  groupByCategory: boolean
+  /** The panel, in Latin-square row order; filled in by the backend when empty. */
+  judges: string[]
+}
+
+export interface BlindMode {
+  salt: string
+  entries: BlindEntry[]
+}
+
+export interface BlindEntry {
+  code: string
+  clipId: string
+  fileSize: number
+  contentHash: string
+  /** Original clip identity, sealed with the organizer key. */
+  sealed: string
+}
+
+export interface BaremeRevisionPin {
+  baremeId: string
+  revision: number | null
+  contentHash: string
 }
 
 export interface NoteData {